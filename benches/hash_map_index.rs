@@ -0,0 +1,79 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use parasol_db::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+use parasol_db::table::vec::VecTable;
+use parasol_db::{Index, Table, View};
+
+const EVENTS: u64 = 10_000;
+const KEYS: u64 = 100;
+
+fn to_insert(kvp: (u64, u64)) -> [HashMapUpdate<u64, u64>; 1] {
+    let (key, value) = kvp;
+    [HashMapUpdate::Insert { key, value }]
+}
+
+// key 0 is written only at the first and last seqs; every other key is rewritten continuously
+fn table() -> VecTable<(u64, u64)> {
+    let mut table = VecTable::new();
+    table.append((0..EVENTS).map(|i| {
+        if i == 0 || i == EVENTS - 1 {
+            (0, 0)
+        } else {
+            (1 + i % (KEYS - 1), i)
+        }
+    }));
+    table
+}
+
+fn index(
+    table: &mut VecTable<(u64, u64)>, seq: u64,
+) -> HashMapIndex<VecTable<(u64, u64)>, u64, u64> {
+    let mut index = HashMapIndex::new(to_insert);
+    index.update(table, seq);
+    index
+}
+
+fn update(c: &mut Criterion) {
+    let table = table();
+    c.bench_function("update contiguous append", |b| {
+        b.iter_batched(
+            || (table.clone(), HashMapIndex::new(to_insert)),
+            |(mut table, mut index)| index.update(&mut table, black_box(EVENTS)),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn get_at_current_seq(c: &mut Criterion) {
+    let mut table = table();
+    let index = index(&mut table, EVENTS);
+    c.bench_function("get at current seq", |b| {
+        b.iter(|| index.get(&mut table, black_box(EVENTS), black_box(&0)))
+    });
+}
+
+fn get_behind_current_seq(c: &mut Criterion) {
+    // worst case: the key was modified since the read seq but last modified before it near zero, so the backward scans
+    // touch every event
+    let mut table = table();
+    let index = index(&mut table, EVENTS);
+    c.bench_function("get behind current seq", |b| {
+        b.iter(|| index.get(&mut table, black_box(EVENTS / 2), black_box(&0)))
+    });
+}
+
+fn get_all(c: &mut Criterion) {
+    let mut table = table();
+    let index = index(&mut table, EVENTS / 2);
+    let current_seq = table.get_current_seq();
+    let mut group = c.benchmark_group("get_all");
+    group.bench_function("ahead of current seq", |b| {
+        b.iter(|| index.get_all(&mut table, black_box(current_seq)))
+    });
+    group.bench_function("behind current seq", |b| {
+        b.iter(|| index.get_all(&mut table, black_box(EVENTS / 4)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, update, get_at_current_seq, get_behind_current_seq, get_all);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+use std::future::{self, Future};
+
+use futures::Stream;
+
+use crate::view::stream::scan_stream;
+use crate::{Seq, View};
+
+/// A view whose events may only be reachable asynchronously, e.g. over the network. Synchronous views can be used as
+/// async views through `AsyncAdapter`. Its streams and futures are `Send`, so they can run on multithreaded executors
+/// like tokio's, though the trait itself only needs the `async` feature.
+pub trait AsyncView {
+    type Event;
+
+    /// Scan the view for events between the given sequences as a stream, with the same semantics as `View::scan`.
+    fn scan(
+        &mut self, start_exclusive: Seq, end_inclusive: Seq,
+    ) -> impl Stream<Item = (Seq, Self::Event)> + Send;
+
+    /// Returns the current sequence number of the view, with the same semantics as `View::get_current_seq`.
+    fn get_current_seq(&mut self) -> impl Future<Output = Seq> + Send;
+}
+
+/// Exposes a synchronous view as an `AsyncView`. Its scans yield to the executor periodically, like `scan_stream`.
+pub struct AsyncAdapter<V: View> {
+    inner: V,
+}
+
+impl<V: View> AsyncAdapter<V> {
+    pub fn new(inner: V) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+/// Requires the view's iterator to be `Send`, since its scans' streams own one.
+impl<V: View> AsyncView for AsyncAdapter<V>
+where
+    V::Iterator: Send,
+{
+    type Event = V::Event;
+
+    fn scan(
+        &mut self, start_exclusive: Seq, end_inclusive: Seq,
+    ) -> impl Stream<Item = (Seq, Self::Event)> + Send {
+        scan_stream(&mut self.inner, start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> impl Future<Output = Seq> + Send {
+        future::ready(self.inner.get_current_seq())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use super::{AsyncAdapter, AsyncView};
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn adapter_matches_view() {
+        let mut view = AsyncAdapter::new(VecTable::<i32>::new());
+        view.inner_mut().append(0..100);
+
+        assert_eq!(block_on(view.get_current_seq()), 100);
+        for (start, end) in [(Seq::MIN, Seq::MAX), (10, 20), (20, 10)] {
+            let streamed = block_on(view.scan(start, end).collect::<Vec<_>>());
+            assert_eq!(streamed, view.inner_mut().scan(start, end).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        let mut view = AsyncAdapter::new(VecTable::<i32>::new());
+        assert_send(view.scan(Seq::MIN, Seq::MAX));
+        assert_send(view.get_current_seq());
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::io;
+
+use crate::{Seq, SeqType};
+
+/// The crate's error type. Its sequence numbers are `Seq` unless another `SeqType` is chosen, as for the views and
+/// tables that return it.
+#[derive(Debug)]
+pub enum Error<S = Seq> {
+    /// Assigning sequence numbers would exceed `Seq::MAX`.
+    SeqOverflow,
+
+    /// A sequence number that must only increase went backwards.
+    NonMonotonic {
+        previous: S,
+        next: S,
+    },
+
+    /// A read requested events from `seq` on, but events before `first_retained` were discarded, e.g. by a table's
+    /// retention.
+    BelowRetention {
+        seq: S,
+        first_retained: S,
+    },
+
+    /// A clock (e.g. a node's entry in a vector clock) moved backwards.
+    ClockRegression {
+        previous: S,
+        next: S,
+    },
+
+    /// A node id doesn't identify any constituent of a composite view.
+    UnknownNode {
+        node_id: usize,
+    },
+
+    Io(io::Error),
+}
+
+impl<S: SeqType> fmt::Display for Error<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SeqOverflow => write!(f, "sequence number overflow"),
+            Error::NonMonotonic { previous, next } => {
+                write!(f, "sequence number {:?} follows {:?}", next, previous)
+            }
+            Error::BelowRetention { seq, first_retained } => {
+                write!(
+                    f,
+                    "sequence number {:?} is before the earliest retained {:?}",
+                    seq, first_retained
+                )
+            }
+            Error::ClockRegression { previous, next } => {
+                write!(f, "clock regressed from {:?} to {:?}", previous, next)
+            }
+            Error::UnknownNode { node_id } => write!(f, "unknown node {}", node_id),
+            Error::Io(err) => write!(f, "i/o error: {}", err),
+        }
+    }
+}
+
+impl<S: SeqType> std::error::Error for Error<S> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<S> From<io::Error> for Error<S> {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::Seq;
+    use std::io;
+
+    #[test]
+    fn io() {
+        let err: Error = io::Error::other("disk full").into();
+        assert!(matches!(err, Error::Io(_)));
+        assert_eq!(err.to_string(), "i/o error: disk full");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Error::<Seq>::SeqOverflow.to_string(), "sequence number overflow");
+        assert_eq!(
+            Error::<Seq>::NonMonotonic { previous: 5, next: 3 }.to_string(),
+            "sequence number 3 follows 5"
+        );
+        assert_eq!(
+            Error::<Seq>::BelowRetention { seq: 3, first_retained: 5 }.to_string(),
+            "sequence number 3 is before the earliest retained 5"
+        );
+        assert_eq!(
+            Error::<u128>::ClockRegression { previous: 5, next: 3 }.to_string(),
+            "clock regressed from 5 to 3"
+        );
+        assert_eq!(Error::<Seq>::UnknownNode { node_id: 2 }.to_string(), "unknown node 2");
+    }
+}
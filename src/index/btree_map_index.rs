@@ -0,0 +1,523 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Bound, RangeBounds};
+
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BTreeMapUpdate<Key, Value>
+where
+    Key: Clone + Ord,
+    Value: Clone,
+{
+    Insert { key: Key, value: Value },
+    Remove { key: Key },
+    Clear,
+}
+
+/// A key that's a sequence, such as a string, whose keys starting with a given prefix form a contiguous range.
+pub trait PrefixKey: Clone + Ord {
+    /// Returns the least key greater than every key starting with `self`, or `None` if there isn't one, e.g. because
+    /// `self` is empty.
+    fn prefix_successor(&self) -> Option<Self>;
+}
+
+impl PrefixKey for Vec<u8> {
+    /// Drops trailing `0xff` bytes, then increments the last byte.
+    fn prefix_successor(&self) -> Option<Self> {
+        let len = self.iter().rposition(|byte| *byte < u8::MAX)? + 1;
+        let mut result = self[..len].to_vec();
+        result[len - 1] += 1;
+        Some(result)
+    }
+}
+
+impl PrefixKey for String {
+    /// Drops trailing `char::MAX`s, then increments the last char, skipping the surrogates, which aren't chars. Strings
+    /// order by their UTF-8 bytes, which order as their chars do.
+    fn prefix_successor(&self) -> Option<Self> {
+        let mut result = self.trim_end_matches(char::MAX).to_string();
+        let last = result.pop()?;
+        let next = match last {
+            '\u{d7ff}' => '\u{e000}',
+            _ => char::from_u32(last as u32 + 1).unwrap(),
+        };
+        result.push(next);
+        Some(result)
+    }
+}
+
+/// An ordered analog of `HashMapIndex` supporting range queries.
+pub struct BTreeMapIndex<Source, Key, Value, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Ord,
+    Value: Clone,
+{
+    current_seq: S,
+    to_assignment: ToAssignment<Source::Event, BTreeMapUpdate<Key, Value>>,
+    map: BTreeMap<Key, Value>,
+}
+
+impl<Source, Key, Value, S> Index<S> for BTreeMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Ord,
+    Value: Clone,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            for update in assignments(&self.to_assignment, event) {
+                match update {
+                    BTreeMapUpdate::Insert { key, value } => {
+                        self.map.insert(key, value);
+                    }
+                    BTreeMapUpdate::Remove { key } => {
+                        self.map.remove(&key);
+                    }
+                    BTreeMapUpdate::Clear => {
+                        self.map.clear();
+                    }
+                }
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, Value, S> BTreeMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Ord,
+    Value: Clone,
+{
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = BTreeMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_assignment: streaming(to_assignment),
+            map: Default::default(),
+        }
+    }
+
+    /// Returns the full map at `seq`.
+    pub fn get_all(&self, source: &mut Source, seq: S) -> BTreeMap<Key, Value> {
+        self.get_range(source, seq, ..)
+    }
+
+    /// Returns the entries whose keys fall in `range` at `seq`.
+    pub fn get_range(
+        &self, source: &mut Source, seq: S, range: impl RangeBounds<Key>,
+    ) -> BTreeMap<Key, Value> {
+        let current = |map: &BTreeMap<Key, Value>| -> BTreeMap<Key, Value> {
+            map.range(range_of(&range))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        };
+
+        if seq >= self.current_seq {
+            // read ahead of current sequence: apply un-applied updates to the current state
+            let mut result = current(&self.map);
+            for (_, event) in source.scan(self.current_seq, seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        BTreeMapUpdate::Insert { key, value } => {
+                            if range.contains(&key) {
+                                result.insert(key, value);
+                            }
+                        }
+                        BTreeMapUpdate::Remove { key } => {
+                            result.remove(&key);
+                        }
+                        BTreeMapUpdate::Clear => {
+                            result.clear();
+                        }
+                    }
+                }
+            }
+            result
+        } else {
+            // read behind current sequence: rewind updates from current state
+            let mut modified_keys = BTreeSet::new();
+            let mut cleared = false;
+
+            // determine which keys in range have changed since the state we're reading at
+            // if the map was cleared, that means all keys have been modified, even ones not in the current map
+            for (_, event) in source.scan(seq, self.current_seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        BTreeMapUpdate::Insert { key, .. } | BTreeMapUpdate::Remove { key } => {
+                            if range.contains(&key) {
+                                modified_keys.insert(key);
+                            }
+                        }
+                        BTreeMapUpdate::Clear => {
+                            cleared = true;
+                        }
+                    }
+                }
+            }
+
+            if cleared {
+                // if the state was cleared since seq, rebuild it from the most recent clear at or before seq
+                // reading backwards, only the first update seen for each key (its most recent) counts
+                let mut decided_keys = BTreeSet::new();
+                let mut result = BTreeMap::new();
+                for (_, event) in source.scan(S::MIN, seq).rev() {
+                    for update in assignments(&self.to_assignment, event).into_iter().rev() {
+                        match update {
+                            BTreeMapUpdate::Clear => {
+                                // every key not yet decided was absent at seq
+                                return result;
+                            }
+                            BTreeMapUpdate::Insert { key, value } => {
+                                if range.contains(&key) && decided_keys.insert(key.clone()) {
+                                    result.insert(key, value);
+                                }
+                            }
+                            BTreeMapUpdate::Remove { key } => {
+                                decided_keys.insert(key);
+                            }
+                        }
+                    }
+                }
+                result
+            } else {
+                // otherwise, look back from seq for the most recent modification to each modified key
+                let mut result = current(&self.map);
+                for (_, event) in source.scan(S::MIN, seq).rev() {
+                    for update in assignments(&self.to_assignment, event).into_iter().rev() {
+                        match update {
+                            BTreeMapUpdate::Clear => {
+                                // remaining keys were not inserted between this clear and seq
+                                for key in &modified_keys {
+                                    result.remove(key);
+                                }
+                                return result;
+                            }
+                            BTreeMapUpdate::Insert { key, value } => {
+                                // only the most recent modification counts
+                                if modified_keys.remove(&key) {
+                                    result.insert(key, value);
+                                }
+                            }
+                            BTreeMapUpdate::Remove { key } => {
+                                // only the most recent modification counts
+                                if modified_keys.remove(&key) {
+                                    result.remove(&key);
+                                }
+                            }
+                        }
+                    }
+
+                    // once we find all modified keys, we're done
+                    if modified_keys.is_empty() {
+                        return result;
+                    }
+                }
+
+                // remaining keys not inserted between 0 and seq
+                for key in &modified_keys {
+                    result.remove(key);
+                }
+                result
+            }
+        }
+    }
+}
+
+impl<Source, Key, Value, S> BTreeMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: PrefixKey,
+    Value: Clone,
+{
+    /// Returns the entries whose keys start with `prefix` at `seq`, in key order.
+    pub fn prefix_scan(&self, source: &mut Source, seq: S, prefix: &Key) -> Vec<(Key, Value)> {
+        let end = prefix
+            .prefix_successor()
+            .map_or(Bound::Unbounded, Bound::Excluded);
+        self.get_range(source, seq, (Bound::Included(prefix.clone()), end))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Copies the bounds of a range so it can be passed to `BTreeMap::range`, which takes it by value.
+fn range_of<Key: Clone>(range: &impl RangeBounds<Key>) -> (Bound<Key>, Bound<Key>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BTreeMapIndex, BTreeMapUpdate, PrefixKey};
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table, View};
+    use std::collections::BTreeMap;
+
+    fn tuple_to_insert<Key: Clone + Ord, Value: Clone>(
+        kvp: (Key, Value),
+    ) -> Vec<BTreeMapUpdate<Key, Value>> {
+        let (key, value) = kvp;
+        vec![BTreeMapUpdate::Insert { key, value }]
+    }
+
+    #[test]
+    fn get_all() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key3", "value3"),
+                ("key4", "value4"),
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut btree_map_index = BTreeMapIndex::new(tuple_to_insert);
+        btree_map_index.update(&mut table, current_seq);
+
+        assert_eq!(current_seq, 4);
+        assert_eq!(btree_map_index.get_current_seq(), 4);
+
+        assert_eq!(btree_map_index.get_all(&mut table, 0), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 1), BTreeMap::from([("key1", "value1")]));
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 2),
+            BTreeMap::from([("key1", "value1"), ("key2", "value2")])
+        );
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 3),
+            BTreeMap::from([("key1", "value1"), ("key2", "value2"), ("key3", "value3")])
+        );
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 4),
+            BTreeMap::from([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key3", "value3"),
+                ("key4", "value4")
+            ])
+        );
+    }
+
+    #[test]
+    fn get_all_overwrite() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key3", "value3"),
+                ("key2", "VALUE2"),
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut btree_map_index = BTreeMapIndex::new(tuple_to_insert);
+        btree_map_index.update(&mut table, current_seq);
+
+        assert_eq!(btree_map_index.get_all(&mut table, 0), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 1), BTreeMap::from([("key1", "value1")]));
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 2),
+            BTreeMap::from([("key1", "value1"), ("key2", "value2")])
+        );
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 3),
+            BTreeMap::from([("key1", "value1"), ("key2", "value2"), ("key3", "value3")])
+        );
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 4),
+            BTreeMap::from([("key1", "value1"), ("key2", "VALUE2"), ("key3", "value3")])
+        );
+    }
+
+    #[test]
+    fn get_all_clear() {
+        let mut table = VecTable::<BTreeMapUpdate<&str, &str>>::new();
+
+        let current_seq = {
+            table.append([
+                BTreeMapUpdate::Insert { key: "key1", value: "value1" },
+                BTreeMapUpdate::Insert { key: "key2", value: "value2" },
+                BTreeMapUpdate::Clear,
+                BTreeMapUpdate::Insert { key: "key3", value: "value3" },
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut btree_map_index =
+            BTreeMapIndex::new(|assignment: BTreeMapUpdate<_, _>| vec![assignment]);
+        btree_map_index.update(&mut table, current_seq);
+
+        assert_eq!(btree_map_index.get_all(&mut table, 0), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 1), BTreeMap::from([("key1", "value1")]));
+        assert_eq!(
+            btree_map_index.get_all(&mut table, 2),
+            BTreeMap::from([("key1", "value1"), ("key2", "value2")])
+        );
+        assert_eq!(btree_map_index.get_all(&mut table, 3), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 4), BTreeMap::from([("key3", "value3")]));
+    }
+
+    #[test]
+    fn get_all_clear_multiple_modifications() {
+        let mut table = VecTable::<BTreeMapUpdate<&str, &str>>::new();
+
+        let current_seq = {
+            table.append([
+                BTreeMapUpdate::Insert { key: "key1", value: "value1" },
+                BTreeMapUpdate::Clear,
+                BTreeMapUpdate::Insert { key: "key1", value: "value1" },
+                BTreeMapUpdate::Remove { key: "key1" },
+                BTreeMapUpdate::Insert { key: "key1", value: "VALUE1" },
+                BTreeMapUpdate::Clear,
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut btree_map_index =
+            BTreeMapIndex::new(|assignment: BTreeMapUpdate<_, _>| vec![assignment]);
+        btree_map_index.update(&mut table, current_seq);
+
+        assert_eq!(btree_map_index.get_all(&mut table, 0), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 1), BTreeMap::from([("key1", "value1")]));
+        assert_eq!(btree_map_index.get_all(&mut table, 2), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 3), BTreeMap::from([("key1", "value1")]));
+        assert_eq!(btree_map_index.get_all(&mut table, 4), BTreeMap::new());
+        assert_eq!(btree_map_index.get_all(&mut table, 5), BTreeMap::from([("key1", "VALUE1")]));
+        assert_eq!(btree_map_index.get_all(&mut table, 6), BTreeMap::new());
+
+        // an index behind the clears reads ahead through them
+        let mut behind = BTreeMapIndex::new(|assignment: BTreeMapUpdate<_, _>| vec![assignment]);
+        behind.update(&mut table, 1);
+        assert_eq!(behind.get_all(&mut table, 2), BTreeMap::new());
+        assert_eq!(behind.get_all(&mut table, 5), BTreeMap::from([("key1", "VALUE1")]));
+    }
+
+    #[test]
+    fn get_range() {
+        let mut table = VecTable::<BTreeMapUpdate<u64, &str>>::new();
+
+        let current_seq = {
+            table.append([
+                BTreeMapUpdate::Insert { key: 1, value: "one" },
+                BTreeMapUpdate::Insert { key: 2, value: "two" },
+                BTreeMapUpdate::Insert { key: 3, value: "three" },
+                BTreeMapUpdate::Insert { key: 4, value: "four" },
+                BTreeMapUpdate::Remove { key: 2 },
+                BTreeMapUpdate::Insert { key: 3, value: "THREE" },
+                BTreeMapUpdate::Insert { key: 5, value: "five" },
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut btree_map_index =
+            BTreeMapIndex::new(|assignment: BTreeMapUpdate<_, _>| vec![assignment]);
+        btree_map_index.update(&mut table, 5);
+
+        // behind current seq
+        assert_eq!(
+            btree_map_index.get_range(&mut table, 4, 2..4),
+            BTreeMap::from([(2, "two"), (3, "three")])
+        );
+        // at current seq
+        assert_eq!(
+            btree_map_index.get_range(&mut table, 5, 2..=4),
+            BTreeMap::from([(3, "three"), (4, "four")])
+        );
+        // ahead of current seq
+        assert_eq!(
+            btree_map_index.get_range(&mut table, current_seq, 3..),
+            BTreeMap::from([(3, "THREE"), (4, "four"), (5, "five")])
+        );
+        assert_eq!(btree_map_index.get_range(&mut table, current_seq, ..1), BTreeMap::new());
+    }
+
+    #[test]
+    fn prefix_successor() {
+        assert_eq!("ab".to_string().prefix_successor(), Some("ac".to_string()));
+        assert_eq!("a\u{10ffff}".to_string().prefix_successor(), Some("b".to_string()));
+        assert_eq!("a\u{d7ff}".to_string().prefix_successor(), Some("a\u{e000}".to_string()));
+        assert_eq!("\u{10ffff}".to_string().prefix_successor(), None);
+        assert_eq!(String::new().prefix_successor(), None);
+
+        assert_eq!(vec![1u8, 2].prefix_successor(), Some(vec![1, 3]));
+        assert_eq!(vec![1u8, 0xff, 0xff].prefix_successor(), Some(vec![2]));
+        assert_eq!(vec![0xffu8].prefix_successor(), None);
+        assert_eq!(Vec::<u8>::new().prefix_successor(), None);
+    }
+
+    #[test]
+    fn prefix_scan() {
+        let mut table = VecTable::<BTreeMapUpdate<String, u64>>::new();
+        let insert = |key: &str, value| BTreeMapUpdate::Insert { key: key.to_string(), value };
+        table.append([
+            insert("ap", 1),
+            insert("apple", 2),
+            insert("apply", 3),
+            insert("apt", 4),
+            insert("aq", 5),
+            insert("b", 6),
+            BTreeMapUpdate::Remove { key: "apple".to_string() },
+            insert("ap\u{10ffff}", 8),
+        ]);
+
+        let mut btree_map_index =
+            BTreeMapIndex::new(|assignment: BTreeMapUpdate<_, _>| [assignment]);
+        btree_map_index.update(&mut table, 6);
+
+        let keys = |entries: Vec<(String, u64)>| -> Vec<String> {
+            entries.into_iter().map(|(key, _)| key).collect()
+        };
+        let prefix = |prefix: &str| prefix.to_string();
+
+        // at current seq
+        assert_eq!(
+            btree_map_index.prefix_scan(&mut table, 6, &prefix("app")),
+            vec![("apple".to_string(), 2), ("apply".to_string(), 3)]
+        );
+        assert_eq!(
+            keys(btree_map_index.prefix_scan(&mut table, 6, &prefix("ap"))),
+            ["ap", "apple", "apply", "apt"]
+        );
+        assert_eq!(
+            keys(btree_map_index.prefix_scan(&mut table, 6, &prefix("c"))),
+            Vec::<String>::new()
+        );
+        assert_eq!(keys(btree_map_index.prefix_scan(&mut table, 6, &prefix(""))).len(), 6);
+
+        // behind current seq
+        assert_eq!(
+            keys(btree_map_index.prefix_scan(&mut table, 3, &prefix("ap"))),
+            ["ap", "apple", "apply"]
+        );
+
+        // ahead of current seq
+        assert_eq!(
+            keys(btree_map_index.prefix_scan(&mut table, 8, &prefix("ap"))),
+            ["ap", "apply", "apt", "ap\u{10ffff}"]
+        );
+        assert_eq!(keys(btree_map_index.prefix_scan(&mut table, 8, &prefix("app"))), ["apply"]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+/// Counts the events per key, e.g. for analytics over an event log. Events are append-only, so counts only grow as the
+/// index moves forward; reading behind the current seq subtracts the events in between.
+pub struct CountIndex<Source, Key, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    current_seq: S,
+    to_keys: ToAssignment<Source::Event, Key>,
+    counts: HashMap<Key, u64>,
+}
+
+impl<Source, Key, S> Index<S> for CountIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            for key in assignments(&self.to_keys, event) {
+                *self.counts.entry(key).or_default() += 1;
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, S> CountIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    /// Each key returned by `to_keys` increments that key's count by one.
+    pub fn new<Keys>(to_keys: impl Fn(Source::Event) -> Keys + Send + 'static) -> Self
+    where
+        Keys: IntoIterator<Item = Key> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_keys: streaming(to_keys),
+            counts: Default::default(),
+        }
+    }
+
+    /// Returns the number of occurrences of a single key at `seq`.
+    pub fn get_count(&self, source: &mut Source, seq: S, key: &Key) -> u64 {
+        let count = self.counts.get(key).copied().unwrap_or_default();
+        let occurrences = source
+            .scan(seq.min(self.current_seq), seq.max(self.current_seq))
+            .flat_map(|(_, event)| assignments(&self.to_keys, event))
+            .filter(|k| k == key)
+            .count() as u64;
+        if seq >= self.current_seq {
+            // read ahead of current sequence: add occurrences since current seq
+            count + occurrences
+        } else {
+            // read behind current sequence: subtract occurrences since seq
+            count - occurrences
+        }
+    }
+
+    /// Returns the number of occurrences of every key that occurred at least once by `seq`.
+    pub fn get_all_counts(&self, source: &mut Source, seq: S) -> HashMap<Key, u64> {
+        let mut result = self.counts.clone();
+        if seq >= self.current_seq {
+            // read ahead of current sequence: add occurrences since current seq
+            for (_, event) in source.scan(self.current_seq, seq) {
+                for key in assignments(&self.to_keys, event) {
+                    *result.entry(key).or_default() += 1;
+                }
+            }
+        } else {
+            // read behind current sequence: subtract occurrences since seq
+            for (_, event) in source.scan(seq, self.current_seq) {
+                for key in assignments(&self.to_keys, event) {
+                    if let Some(count) = result.get_mut(&key) {
+                        *count -= 1;
+                    }
+                }
+            }
+            result.retain(|_, count| *count > 0);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CountIndex;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+    use std::collections::HashMap;
+    use std::str::SplitWhitespace;
+
+    fn words(event: &'static str) -> SplitWhitespace<'static> {
+        event.split_whitespace()
+    }
+
+    #[test]
+    fn get_count() {
+        let mut table = VecTable::<&str>::new();
+        table.append(["a b", "a", "c a", "b"]);
+
+        let mut count_index = CountIndex::new(words);
+        count_index.update(&mut table, 2);
+        assert_eq!(count_index.get_current_seq(), 2);
+
+        // behind
+        assert_eq!(count_index.get_count(&mut table, 0, &"a"), 0);
+        assert_eq!(count_index.get_count(&mut table, 1, &"a"), 1);
+        assert_eq!(count_index.get_count(&mut table, 1, &"b"), 1);
+
+        // at
+        assert_eq!(count_index.get_count(&mut table, 2, &"a"), 2);
+        assert_eq!(count_index.get_count(&mut table, 2, &"c"), 0);
+
+        // ahead
+        assert_eq!(count_index.get_count(&mut table, 3, &"a"), 3);
+        assert_eq!(count_index.get_count(&mut table, 4, &"b"), 2);
+        assert_eq!(count_index.get_count(&mut table, 4, &"c"), 1);
+        assert_eq!(count_index.get_count(&mut table, 4, &"d"), 0);
+    }
+
+    #[test]
+    fn get_all_counts() {
+        let mut table = VecTable::<&str>::new();
+        table.append(["a b", "a", "c a", "b"]);
+
+        let mut count_index = CountIndex::new(words);
+        count_index.update(&mut table, 2);
+
+        assert_eq!(count_index.get_all_counts(&mut table, 0), HashMap::new());
+        assert_eq!(count_index.get_all_counts(&mut table, 1), HashMap::from([("a", 1), ("b", 1)]));
+        assert_eq!(count_index.get_all_counts(&mut table, 2), HashMap::from([("a", 2), ("b", 1)]));
+        assert_eq!(
+            count_index.get_all_counts(&mut table, 3),
+            HashMap::from([("a", 3), ("b", 1), ("c", 1)])
+        );
+        assert_eq!(
+            count_index.get_all_counts(&mut table, 4),
+            HashMap::from([("a", 3), ("b", 2), ("c", 1)])
+        );
+    }
+
+    #[test]
+    fn update_backward() {
+        let mut table = VecTable::<&str>::new();
+        table.append(["a b", "a", "c a", "b"]);
+
+        let mut count_index = CountIndex::new(words);
+        count_index.update(&mut table, 3);
+        count_index.update(&mut table, 1);
+        assert_eq!(count_index.get_current_seq(), 3);
+
+        // updating forward again doesn't double count events already incorporated
+        count_index.update(&mut table, 4);
+        assert_eq!(
+            count_index.get_all_counts(&mut table, 4),
+            HashMap::from([("a", 3), ("b", 2), ("c", 1)])
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::index::hash_map_index::HashMapUpdate;
+
+/// Returns the minimal set of updates that transforms `before` into `after`: an insert for each key that was added or
+/// whose value changed, and a remove for each key that was removed. Combined with `HashMapIndex::get_all` at two seqs,
+/// this yields the logical changeset between them without scanning events.
+pub fn diff_maps<K, V>(before: &HashMap<K, V>, after: &HashMap<K, V>) -> Vec<HashMapUpdate<K, V>>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+{
+    let mut result = Vec::new();
+    for key in before.keys() {
+        if !after.contains_key(key) {
+            result.push(HashMapUpdate::Remove { key: key.clone() });
+        }
+    }
+    for (key, value) in after {
+        if before.get(key) != Some(value) {
+            result.push(HashMapUpdate::Insert { key: key.clone(), value: value.clone() });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_maps;
+    use crate::index::hash_map_index::HashMapUpdate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn diff_none() {
+        let map = HashMap::from([("key1", "value1")]);
+        assert_eq!(diff_maps(&map, &map), vec![]);
+        assert_eq!(diff_maps::<&str, &str>(&HashMap::new(), &HashMap::new()), vec![]);
+    }
+
+    #[test]
+    fn diff_added() {
+        let before = HashMap::from([("key1", "value1")]);
+        let after = HashMap::from([("key1", "value1"), ("key2", "value2")]);
+        assert_eq!(
+            diff_maps(&before, &after),
+            vec![HashMapUpdate::Insert { key: "key2", value: "value2" }]
+        );
+    }
+
+    #[test]
+    fn diff_removed() {
+        let before = HashMap::from([("key1", "value1"), ("key2", "value2")]);
+        let after = HashMap::from([("key1", "value1")]);
+        assert_eq!(diff_maps(&before, &after), vec![HashMapUpdate::Remove { key: "key2" }]);
+    }
+
+    #[test]
+    fn diff_changed() {
+        let before = HashMap::from([("key1", "value1"), ("key2", "value2"), ("key3", "value3")]);
+        let after = HashMap::from([("key1", "value1"), ("key2", "VALUE2"), ("key4", "value4")]);
+
+        let diff = diff_maps(&before, &after);
+        assert_eq!(diff.len(), 3);
+        assert!(diff.contains(&HashMapUpdate::Insert { key: "key2", value: "VALUE2" }));
+        assert!(diff.contains(&HashMapUpdate::Remove { key: "key3" }));
+        assert!(diff.contains(&HashMapUpdate::Insert { key: "key4", value: "value4" }));
+    }
+}
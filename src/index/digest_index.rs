@@ -0,0 +1,145 @@
+use sha2::{Digest, Sha256};
+
+use crate::{Index, Seq, SeqType, View};
+
+/// Maintains a running hash chain over the event stream, `digest_n = sha256(digest_{n-1} || to_bytes(event_n))`, starting
+/// from all zeroes. Two replicas with equal digests at a seq hold identical event histories up to that seq.
+pub struct DigestIndex<Source, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+{
+    current_seq: S,
+    to_bytes: Box<dyn Fn(Source::Event) -> Vec<u8> + Send>,
+    digest: [u8; 32],
+    checkpoint_interval: usize,
+    events_since_checkpoint: usize,
+    checkpoints: Vec<(S, [u8; 32])>,
+}
+
+impl<Source, S> Index<S> for DigestIndex<Source, S>
+where
+    Source: View<S>,
+    S: SeqType,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (event_seq, event) in source.scan(self.current_seq, seq) {
+            self.digest = chain(&self.digest, &(self.to_bytes)(event));
+
+            // checkpoint periodically so historical reads don't need to rehash from the beginning
+            self.events_since_checkpoint += 1;
+            if self.events_since_checkpoint >= self.checkpoint_interval {
+                self.checkpoints.push((event_seq, self.digest));
+                self.events_since_checkpoint = 0;
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, S> DigestIndex<Source, S>
+where
+    Source: View<S>,
+    S: SeqType,
+{
+    /// `to_bytes` serializes each event into the chain. A checkpoint of the digest is stored every
+    /// `checkpoint_interval` events to bound the cost of `digest_at`.
+    pub fn new(
+        to_bytes: impl Fn(Source::Event) -> Vec<u8> + Send + 'static, checkpoint_interval: usize,
+    ) -> Self {
+        Self {
+            current_seq: Default::default(),
+            to_bytes: Box::new(to_bytes),
+            digest: [0; 32],
+            checkpoint_interval: checkpoint_interval.max(1),
+            events_since_checkpoint: 0,
+            checkpoints: vec![(S::MIN, [0; 32])],
+        }
+    }
+
+    /// Returns the digest of all events up to and including `seq`.
+    pub fn digest_at(&self, source: &mut Source, seq: S) -> [u8; 32] {
+        // start from the latest known digest at or before seq: the current one if we're reading ahead, otherwise the
+        // nearest checkpoint
+        let (start_seq, mut result) = if seq >= self.current_seq {
+            (self.current_seq, self.digest)
+        } else {
+            let idx = self
+                .checkpoints
+                .partition_point(|(checkpoint_seq, _)| *checkpoint_seq <= seq);
+            self.checkpoints[idx - 1]
+        };
+
+        for (_, event) in source.scan(start_seq, seq) {
+            result = chain(&result, &(self.to_bytes)(event));
+        }
+        result
+    }
+}
+
+fn chain(digest: &[u8; 32], bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(digest);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DigestIndex;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    fn to_bytes(event: i32) -> Vec<u8> {
+        event.to_le_bytes().to_vec()
+    }
+
+    #[test]
+    fn identical_streams() {
+        let mut table1 = VecTable::<i32>::new();
+        let mut table2 = VecTable::<i32>::new();
+        table1.append([12, 34, 56, 78, 90]);
+        table2.append([12, 34, 56, 78, 90]);
+
+        let mut index1 = DigestIndex::new(to_bytes, 2);
+        index1.update(&mut table1, 5);
+        let mut index2 = DigestIndex::new(to_bytes, 3);
+        index2.update(&mut table2, 3);
+
+        for seq in 0..=5 {
+            assert_eq!(index1.digest_at(&mut table1, seq), index2.digest_at(&mut table2, seq));
+        }
+        assert_eq!(index1.digest_at(&mut table1, 0), [0; 32]);
+        assert_ne!(index1.digest_at(&mut table1, 1), index1.digest_at(&mut table1, 2));
+    }
+
+    #[test]
+    fn diverging_streams() {
+        let mut table1 = VecTable::<i32>::new();
+        let mut table2 = VecTable::<i32>::new();
+        table1.append([12, 34, 56, 78, 90]);
+        table2.append([12, 34, 65, 78, 90]);
+
+        let mut index1 = DigestIndex::new(to_bytes, 2);
+        index1.update(&mut table1, 5);
+        let mut index2 = DigestIndex::new(to_bytes, 2);
+        index2.update(&mut table2, 5);
+
+        for seq in 0..=2 {
+            assert_eq!(index1.digest_at(&mut table1, seq), index2.digest_at(&mut table2, seq));
+        }
+        for seq in 3..=5 {
+            assert_ne!(index1.digest_at(&mut table1, seq), index2.digest_at(&mut table2, seq));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Index, Seq, SeqType, View};
+
+type ToKeys<Event, Key> = Box<dyn Fn(&Event) -> Vec<Key> + Send>;
+
+/// Groups events by keys derived from each event, keeping each group's events in seq order, e.g. to list a customer's
+/// orders. Each event is kept along with its seq, so reads behind the current seq truncate groups rather than rewind.
+pub struct GroupByIndex<Source, Key, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Source::Event: Clone,
+    Key: Clone + Eq + Hash,
+{
+    current_seq: S,
+    to_keys: ToKeys<Source::Event, Key>,
+    groups: HashMap<Key, Vec<(S, Source::Event)>>,
+}
+
+impl<Source, Key, S> Index<S> for GroupByIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Source::Event: Clone,
+    Key: Clone + Eq + Hash,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (event_seq, event) in source.scan(self.current_seq, seq) {
+            for key in (self.to_keys)(&event) {
+                self.groups
+                    .entry(key)
+                    .or_default()
+                    .push((event_seq, event.clone()));
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, S> GroupByIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Source::Event: Clone,
+    Key: Clone + Eq + Hash,
+{
+    /// Each event is added to the group of each key returned by `to_keys`, or to no group if it returns none.
+    pub fn new<Keys>(to_keys: impl Fn(&Source::Event) -> Keys + Send + 'static) -> Self
+    where
+        Keys: IntoIterator<Item = Key>,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_keys: Box::new(move |event| to_keys(event).into_iter().collect()),
+            groups: Default::default(),
+        }
+    }
+
+    /// Returns the events in a single group at `seq`, in seq order.
+    pub fn get_group(&self, source: &mut Source, seq: S, key: &Key) -> Vec<Source::Event> {
+        let group = self.groups.get(key).map(Vec::as_slice).unwrap_or_default();
+        if seq >= self.current_seq {
+            // read ahead of current sequence: append the group's events since current seq
+            let mut result = group
+                .iter()
+                .map(|(_, event)| event.clone())
+                .collect::<Vec<_>>();
+            result.extend(
+                source
+                    .scan(self.current_seq, seq)
+                    .map(|(_, event)| event)
+                    .filter(|event| (self.to_keys)(event).contains(key)),
+            );
+            result
+        } else {
+            // read behind current sequence: drop the group's events after seq
+            let len = group.partition_point(|(event_seq, _)| *event_seq <= seq);
+            group[..len]
+                .iter()
+                .map(|(_, event)| event.clone())
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GroupByIndex;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    fn parity(event: &i32) -> bool {
+        event % 2 == 0
+    }
+
+    #[test]
+    fn get_group() {
+        let mut table = VecTable::<i32>::new();
+        table.append([1, 2, 3, 4, 6, 5]);
+
+        let mut group_by_index = GroupByIndex::new(|event| [parity(event)]);
+        group_by_index.update(&mut table, 3);
+
+        // behind
+        assert_eq!(group_by_index.get_group(&mut table, 0, &false), Vec::<i32>::new());
+        assert_eq!(group_by_index.get_group(&mut table, 1, &false), vec![1]);
+        assert_eq!(group_by_index.get_group(&mut table, 2, &true), vec![2]);
+
+        // at
+        assert_eq!(group_by_index.get_group(&mut table, 3, &false), vec![1, 3]);
+        assert_eq!(group_by_index.get_group(&mut table, 3, &true), vec![2]);
+
+        // ahead
+        assert_eq!(group_by_index.get_group(&mut table, 5, &true), vec![2, 4, 6]);
+        assert_eq!(group_by_index.get_group(&mut table, 6, &false), vec![1, 3, 5]);
+
+        group_by_index.update(&mut table, 6);
+        assert_eq!(group_by_index.get_group(&mut table, 4, &true), vec![2, 4]);
+        assert_eq!(group_by_index.get_group(&mut table, 6, &false), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn multiple_groups() {
+        let mut table = VecTable::<&str>::new();
+        table.append(["a b", "b", "", "a c"]);
+
+        let mut group_by_index = GroupByIndex::new(|event: &&str| event.split_whitespace());
+        group_by_index.update(&mut table, 2);
+
+        assert_eq!(group_by_index.get_group(&mut table, 2, &"a"), vec!["a b"]);
+        assert_eq!(group_by_index.get_group(&mut table, 2, &"b"), vec!["a b", "b"]);
+        assert_eq!(group_by_index.get_group(&mut table, 4, &"a"), vec!["a b", "a c"]);
+        assert_eq!(group_by_index.get_group(&mut table, 4, &"c"), vec!["a c"]);
+        assert_eq!(group_by_index.get_group(&mut table, 1, &"b"), vec!["a b"]);
+    }
+}
@@ -1,9 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::{Index, Seq, View};
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
 
-#[derive(Clone)]
+/// How many events `update_cancellable` processes between checks of its cancellation flag.
+const CANCELLATION_CHECK_INTERVAL: usize = 16;
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum HashMapUpdate<Key, Value>
 where
     Key: Clone + Eq + Hash,
@@ -14,66 +19,401 @@ where
     Clear,
 }
 
-pub struct HashMapIndex<Source, Key, Value>
+/// The state of a single key at a sequence number, distinguishing a key that was removed from one that was never set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyState<Value> {
+    Present(Value),
+    /// The key's most recent modification was a `Remove` or `Clear`.
+    Removed,
+    /// The key was never modified.
+    Absent,
+}
+
+/// The persistable state of a `HashMapIndex`: its map as of its current sequence number. With the `serde` feature it can
+/// be serialized, e.g. to persist alongside the log, and restored with `HashMapIndex::resume` or `from_checkpoint`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Key: serde::Serialize, Value: serde::Serialize, S: serde::Serialize",
+        deserialize = "Key: Eq + Hash + serde::Deserialize<'de>, Value: serde::Deserialize<'de>, \
+                       S: serde::Deserialize<'de>"
+    ))
+)]
+pub struct HashMapIndexSnapshot<Key, Value, S = Seq> {
+    pub seq: S,
+    pub map: HashMap<Key, Value>,
+}
+
+/// A `HashMapIndexSnapshot` as returned by `HashMapIndex::checkpoint` and restored by `HashMapIndex::from_checkpoint`.
+pub type HashMapCheckpoint<Key, Value, S = Seq> = HashMapIndexSnapshot<Key, Value, S>;
+
+/// An immutable copy of a `HashMapIndex`'s map as of its current sequence number, queryable without the source, e.g. from
+/// another thread.
+#[derive(Clone, Debug)]
+pub struct FrozenIndex<Key, Value, S = Seq> {
+    seq: S,
+    map: HashMap<Key, Value>,
+}
+
+impl<Key: Eq + Hash, Value, S: SeqType> FrozenIndex<Key, Value, S> {
+    /// Returns the sequence number the index was frozen at.
+    pub fn seq(&self) -> S {
+        self.seq
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&Value> {
+        self.map.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Value)> {
+        self.map.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// The update fan-out of a `HashMapIndex`'s `to_assignment` over a range of events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssignmentStats {
+    pub events: usize,
+    pub updates: usize,
+    pub max_fan_out: usize,
+}
+
+/// Maps materialized periodically by `update`, so `get_all` can replay forward from the nearest one rather than rewind
+/// from the current state.
+struct Snapshots<Key, Value, S> {
+    // capture a snapshot every `interval` events, if set, keeping at most `max`
+    interval: Option<usize>,
+    max: usize,
+    events_since: usize,
+    // the seq of a snapshot that's due, taken once every event at that seq is applied
+    pending: Option<S>,
+    maps: BTreeMap<S, HashMap<Key, Value>>,
+}
+
+impl<Key, Value, S> Default for Snapshots<Key, Value, S> {
+    fn default() -> Self {
+        Self { interval: None, max: 0, events_since: 0, pending: None, maps: BTreeMap::new() }
+    }
+}
+
+impl<Key: Clone, Value: Clone, S: SeqType> Snapshots<Key, Value, S> {
+    /// Takes a due snapshot if the event about to be applied, if any, has a later seq.
+    fn before_event(&mut self, next_seq: Option<S>, map: &HashMap<Key, Value>) {
+        if let Some(seq) = self.pending {
+            if next_seq.is_none_or(|next_seq| next_seq > seq) {
+                self.pending = None;
+                self.maps.insert(seq, map.clone());
+                // drop the oldest snapshot, so reads far in the past fall back to rewinding
+                if self.maps.len() > self.max {
+                    self.maps.pop_first();
+                }
+            }
+        }
+    }
+
+    fn after_event(&mut self, seq: S) {
+        if let Some(interval) = self.interval {
+            self.events_since += 1;
+            if self.events_since >= interval {
+                self.events_since = 0;
+                self.pending = Some(seq);
+            }
+        }
+    }
+}
+
+type OnUpdate<Key, Value, S> = Box<dyn FnMut(S, &HashMapUpdate<Key, Value>) + Send>;
+
+pub struct HashMapIndex<Source, Key, Value, S = Seq>
 where
-    Source: View,
+    Source: View<S>,
+    S: SeqType,
     Key: Clone + Eq + Hash,
     Value: Clone,
 {
-    current_seq: Seq,
-    to_assignment: fn(Source::Event) -> Vec<HashMapUpdate<Key, Value>>,
+    current_seq: S,
+    to_assignment: ToAssignment<Source::Event, HashMapUpdate<Key, Value>>,
+    max_backward_scan: Option<usize>,
+    first_consumed_seq: Option<S>,
+    snapshots: Snapshots<Key, Value, S>,
+    on_update: Option<OnUpdate<Key, Value, S>>,
     map: HashMap<Key, Value>,
 }
 
-impl<Source, Key, Value> Index for HashMapIndex<Source, Key, Value>
+impl<Source, Key, Value, S> Index<S> for HashMapIndex<Source, Key, Value, S>
 where
-    Source: View,
+    Source: View<S>,
+    S: SeqType,
     Key: Clone + Eq + Hash,
     Value: Clone,
 {
     type Source = Source;
 
-    fn update(&mut self, source: &mut Self::Source, seq: Seq) {
-        for (_, event) in source.scan(self.current_seq, seq) {
-            for update in (self.to_assignment)(event) {
-                match update {
-                    HashMapUpdate::Insert { key, value } => {
-                        self.map.insert(key, value);
-                    }
-                    HashMapUpdate::Remove { key } => {
-                        self.map.remove(&key);
-                    }
-                    HashMapUpdate::Clear => {
-                        self.map.clear();
-                    }
-                }
-            }
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (event_seq, event) in source.scan(self.current_seq, seq) {
+            self.apply_event(event_seq, event, |_, _| {});
         }
+        self.snapshots.before_event(None, &self.map);
 
         self.current_seq = seq;
     }
 
-    fn get_current_seq(&self) -> Seq {
+    fn get_current_seq(&self) -> S {
         self.current_seq
     }
 }
 
-impl<Source, Key, Value> HashMapIndex<Source, Key, Value>
+impl<Source, Key, Value, S> HashMapIndex<Source, Key, Value, S>
 where
-    Source: View,
+    Source: View<S>,
+    S: SeqType,
     Key: Clone + Eq + Hash,
     Value: Clone,
 {
-    pub fn new(to_assignment: fn(Source::Event) -> Vec<HashMapUpdate<Key, Value>>) -> Self {
-        Self { current_seq: Default::default(), to_assignment, map: Default::default() }
+    /// Builds an index whose `to_assignment` maps each event to its updates. Any `IntoIterator` works, so events with a
+    /// single update can return `iter::once(..)` or an array rather than allocating a `Vec`.
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self::new_with_max_backward_scan(to_assignment, None)
+    }
+
+    /// Like `new`, but when `get` has scanned back more than `max_backward_scan` events from the read seq looking for a
+    /// key's most recent modification, it gives up and computes the full map at the read seq instead.
+    pub fn new_with_max_backward_scan<Updates>(
+        to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static,
+        max_backward_scan: Option<usize>,
+    ) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_assignment: streaming(to_assignment),
+            max_backward_scan,
+            first_consumed_seq: None,
+            snapshots: Default::default(),
+            on_update: None,
+            map: Default::default(),
+        }
+    }
+
+    /// Like `new`, but `update` keeps a copy of the map every `snapshot_interval` events, retaining the most recent
+    /// `max_snapshots`, and `get_all` behind the current seq replays forward from the nearest snapshot at or before the
+    /// read seq, bounding its cost by the interval. Each snapshot is a full copy of the map, so memory grows with
+    /// `max_snapshots` times the size of the map; reads older than every retained snapshot rewind as usual.
+    ///
+    /// Panics if `snapshot_interval` is 0.
+    pub fn with_snapshot_interval<Updates>(
+        to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static,
+        snapshot_interval: usize, max_snapshots: usize,
+    ) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        assert!(snapshot_interval > 0, "snapshot interval must be positive");
+        let mut result = Self::new(to_assignment);
+        result.snapshots.interval = Some(snapshot_interval);
+        result.snapshots.max = max_snapshots;
+        result
+    }
+
+    /// Starts the index with the given map at `initial_seq`, so subsequent updates apply on top of it. Historical reads
+    /// below `initial_seq` aren't supported because there are no events to rewind through.
+    pub fn with_initial<Updates>(
+        to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static,
+        initial: HashMap<Key, Value>, initial_seq: S,
+    ) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: initial_seq,
+            to_assignment: streaming(to_assignment),
+            max_backward_scan: None,
+            first_consumed_seq: None,
+            snapshots: Default::default(),
+            on_update: None,
+            map: initial,
+        }
+    }
+
+    /// Loads a snapshot then updates the index to the source's current sequence number, catching up on any events
+    /// appended since the snapshot was taken.
+    pub fn resume<Updates>(
+        snapshot: HashMapIndexSnapshot<Key, Value, S>, source: &mut Source,
+        to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static,
+    ) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        let mut result = Self::with_initial(to_assignment, snapshot.map, snapshot.seq);
+        let seq = source.get_current_seq();
+        result.update(source, seq);
+        result
+    }
+
+    /// Restores an index from a checkpoint without reading the source; its next `update` picks up from the checkpoint's
+    /// sequence number. Like `with_initial`, it can't read behind that sequence number.
+    pub fn from_checkpoint<Updates>(
+        to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static,
+        checkpoint: HashMapCheckpoint<Key, Value, S>,
+    ) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self::with_initial(to_assignment, checkpoint.map, checkpoint.seq)
+    }
+
+    /// Sets a callback invoked with each update as it's applied to the map, along with the seq of its event, in event
+    /// order, e.g. to stream changes downstream. It's called by `update` and its variants and by `apply_changeset` (with
+    /// the changeset's seq), but not by reads, which replay events without applying them. Replaces any previous callback.
+    pub fn on_update(&mut self, f: impl FnMut(S, &HashMapUpdate<Key, Value>) + Send + 'static) {
+        self.on_update = Some(Box::new(f));
+    }
+
+    /// Like `update`, but checks `should_stop` every few events and, if it's set, returns early. Returns the sequence
+    /// number reached, which is also the index's new current sequence number; the index is consistent at that seq and
+    /// a later update will pick up where this one left off.
+    pub fn update_cancellable(
+        &mut self, source: &mut Source, seq: S, should_stop: &AtomicBool,
+    ) -> S {
+        if seq < self.current_seq {
+            return self.current_seq;
+        }
+        for (idx, (event_seq, event)) in source.scan(self.current_seq, seq).enumerate() {
+            // only stop between sequence numbers so we never leave an event with the current seq unapplied
+            if idx % CANCELLATION_CHECK_INTERVAL == 0
+                && event_seq > self.current_seq
+                && should_stop.load(Ordering::Relaxed)
+            {
+                self.snapshots.before_event(None, &self.map);
+                return self.current_seq;
+            }
+            self.apply_event(event_seq, event, |_, _| {});
+        }
+        self.snapshots.before_event(None, &self.map);
+
+        self.current_seq = seq;
+        seq
+    }
+
+    /// Like `update`, but returns the net changes to the map, as `diff_maps` would between the maps before and after, e.g.
+    /// to push deltas to downstream consumers: an insert for each key added or changed and a remove for each key
+    /// removed. Keys that end where they started, e.g. inserted then removed, are left out.
+    pub fn update_with_diff(
+        &mut self, source: &mut Source, seq: S,
+    ) -> Vec<HashMapUpdate<Key, Value>>
+    where
+        Value: PartialEq,
+    {
+        if seq < self.current_seq {
+            return Vec::new();
+        }
+        // the value of each touched key before the update
+        let mut before = HashMap::new();
+        for (event_seq, event) in source.scan(self.current_seq, seq) {
+            self.apply_event(event_seq, event, |map, update| match update {
+                HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
+                    if !before.contains_key(key) {
+                        before.insert(key.clone(), map.get(key).cloned());
+                    }
+                }
+                HashMapUpdate::Clear => {
+                    for (key, value) in map {
+                        before
+                            .entry(key.clone())
+                            .or_insert_with(|| Some(value.clone()));
+                    }
+                }
+            });
+        }
+        self.snapshots.before_event(None, &self.map);
+        self.current_seq = seq;
+
+        before
+            .into_iter()
+            .filter_map(|(key, before)| match self.map.get(&key) {
+                Some(value) if before.as_ref() != Some(value) => {
+                    Some(HashMapUpdate::Insert { key, value: value.clone() })
+                }
+                None if before.is_some() => Some(HashMapUpdate::Remove { key }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Applies a precomputed changeset (e.g. from `diff_maps`) directly to the index and advances it to `new_seq`. This
+    /// bypasses the source, so the index and its source can diverge; it's intended for indexes fed by a remote
+    /// changeset rather than a local log.
+    pub fn apply_changeset(&mut self, changes: &[HashMapUpdate<Key, Value>], new_seq: S) {
+        for update in changes {
+            if let Some(on_update) = &mut self.on_update {
+                on_update(new_seq, update);
+            }
+            match update {
+                HashMapUpdate::Insert { key, value } => {
+                    self.map.insert(key.clone(), value.clone());
+                }
+                HashMapUpdate::Remove { key } => {
+                    self.map.remove(key);
+                }
+                HashMapUpdate::Clear => {
+                    self.map.clear();
+                }
+            }
+        }
+
+        self.current_seq = new_seq;
+    }
+
+    /// Returns the sequence number of the first source event the index consumed (or its current sequence number if it
+    /// hasn't consumed any) and its current sequence number. Rewinding the index is only valid while the source still retains the
+    /// events in this range, i.e. while the source's `first_seq` is at or before the start of it.
+    pub fn covered_range(&self) -> (S, S) {
+        (self.first_consumed_seq.unwrap_or(self.current_seq), self.current_seq)
+    }
+
+    /// Returns the index's state as of its current sequence number, e.g. to persist and later `resume` from.
+    pub fn snapshot(&self) -> HashMapIndexSnapshot<Key, Value, S> {
+        HashMapIndexSnapshot { seq: self.current_seq, map: self.map.clone() }
+    }
+
+    /// Like `snapshot`, for restoring with `from_checkpoint`.
+    pub fn checkpoint(&self) -> HashMapCheckpoint<Key, Value, S> {
+        self.snapshot()
+    }
+
+    /// Returns an immutable copy of the index's map as of its current sequence number.
+    pub fn freeze(&self) -> FrozenIndex<Key, Value, S> {
+        FrozenIndex { seq: self.current_seq, map: self.map.clone() }
+    }
+
+    /// Collects the updates for an event, e.g. to apply them in reverse.
+    fn assignments(&self, event: Source::Event) -> Vec<HashMapUpdate<Key, Value>> {
+        assignments(&self.to_assignment, event)
     }
 
     /// Returns the value associated with a single key at `seq`.
-    pub fn get(&self, source: &mut Source, seq: Seq, key: &Key) -> Option<Value> {
+    pub fn get(&self, source: &mut Source, seq: S, key: &Key) -> Option<Value> {
         if seq >= self.current_seq {
             // read backwards from read seq to current seq
             for (_, event) in source.scan(self.current_seq, seq).rev() {
-                for update in (self.to_assignment)(event).into_iter().rev() {
+                for update in self.assignments(event).into_iter().rev() {
                     match update {
                         HashMapUpdate::Insert { key: update_key, value } => {
                             if key == &update_key {
@@ -101,7 +441,7 @@ where
             // read backwards from current seq to read seq to find most recent modification (if any) since current seq
             let mut modified = false;
             for (_, event) in source.scan(seq, self.current_seq).rev() {
-                for update in (self.to_assignment)(event).into_iter().rev() {
+                for update in self.assignments(event).into_iter().rev() {
                     match update {
                         HashMapUpdate::Insert { key: update_key, .. } => {
                             if key == &update_key {
@@ -128,8 +468,13 @@ where
 
             if modified {
                 // if it's been modified, read backwards from seq until we find its most recent modification
-                for (_, event) in source.scan(0, seq).rev() {
-                    for update in (self.to_assignment)(event).into_iter().rev() {
+                for (scanned, (_, event)) in source.scan(S::MIN, seq).rev().enumerate() {
+                    if self.max_backward_scan.is_some_and(|max| scanned >= max) {
+                        // scanned too far back: switch strategies and rewind the full map from the current state
+                        return self.get_all(source, seq).remove(key);
+                    }
+
+                    for update in self.assignments(event).into_iter().rev() {
                         match update {
                             HashMapUpdate::Insert { key: update_key, value } => {
                                 if key == &update_key {
@@ -160,27 +505,125 @@ where
         }
     }
 
-    /// Returns the full map at `seq`.
-    pub fn get_all(&self, source: &mut Source, seq: Seq) -> HashMap<Key, Value> {
-        if seq >= self.current_seq {
-            // read ahead of current sequence: apply un-applied updates to clone of current state
-            let mut result = self.map.clone();
-            for (_, event) in source.scan(self.current_seq, seq) {
-                for update in (self.to_assignment)(event) {
-                    match update {
-                        HashMapUpdate::Insert { key, value } => {
-                            result.insert(key, value);
-                        }
-                        HashMapUpdate::Remove { key } => {
-                            result.remove(&key);
-                        }
-                        HashMapUpdate::Clear => {
-                            result.clear();
+    /// Returns the state of a single key at `seq`. A missing key's history is read backwards from `seq` until its most
+    /// recent modification, so telling `Absent` from `Removed` can scan back to the first event.
+    pub fn get_state(&self, source: &mut Source, seq: S, key: &Key) -> KeyState<Value> {
+        if let Some(value) = self.get(source, seq, key) {
+            return KeyState::Present(value);
+        }
+
+        // the key has no value, so its most recent modification (if any) was a removal
+        for (_, event) in source.scan(S::MIN, seq).rev() {
+            for update in self.assignments(event).into_iter().rev() {
+                match update {
+                    HashMapUpdate::Insert { key: update_key, .. }
+                    | HashMapUpdate::Remove { key: update_key } => {
+                        if key == &update_key {
+                            return KeyState::Removed;
                         }
                     }
+                    HashMapUpdate::Clear => return KeyState::Removed,
+                }
+            }
+        }
+        KeyState::Absent
+    }
+
+    /// Returns whether `key` is present at `seq`. Like `get`, this stops scanning at the key's most recent modification.
+    pub fn contains_key(&self, source: &mut Source, seq: S, key: &Key) -> bool {
+        self.get(source, seq, key).is_some()
+    }
+
+    /// Returns the value `key` had before its most recent modification (as of the current seq), along with the sequence
+    /// number of the event that set it. Returns `None` if the key had no value before its most recent modification,
+    /// including when it has only been modified once.
+    pub fn get_previous(&self, source: &mut Source, key: &Key) -> Option<(S, Value)> {
+        let mut found_current = false;
+        for (seq, event) in source.scan(S::MIN, self.current_seq).rev() {
+            for update in self.assignments(event).into_iter().rev() {
+                let (modified, value) = match update {
+                    HashMapUpdate::Insert { key: update_key, value } => {
+                        (key == &update_key, Some(value))
+                    }
+                    HashMapUpdate::Remove { key: update_key } => (key == &update_key, None),
+                    HashMapUpdate::Clear => (true, None),
+                };
+                if modified {
+                    if found_current {
+                        // this is the modification before the most recent one
+                        return value.map(|value| (seq, value));
+                    }
+                    // this is the most recent modification; keep going to find the one before it
+                    found_current = true;
                 }
             }
-            result
+        }
+
+        None
+    }
+
+    /// Returns the keys inserted or removed by events between `start_exclusive` and `end_inclusive`, and whether the map
+    /// was cleared in that range, in which case every key should be considered changed.
+    pub fn changed_keys(
+        &self, source: &mut Source, start_exclusive: S, end_inclusive: S,
+    ) -> (HashSet<Key>, bool) {
+        let mut changed_keys = HashSet::new();
+        let mut cleared = false;
+        for (_, event) in source.scan(start_exclusive, end_inclusive) {
+            (self.to_assignment)(event, &mut |update| match update {
+                HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
+                    changed_keys.insert(key);
+                }
+                HashMapUpdate::Clear => {
+                    cleared = true;
+                }
+            });
+        }
+        (changed_keys, cleared)
+    }
+
+    /// Returns the updates made by events after `from` up to the current seq, in sequence order, each with the sequence
+    /// number of its event. Applying them to the map at `from` (e.g. `get_all(source, from)`) yields the map at the
+    /// current seq, so a consumer can mirror the index incrementally, checkpointing the last sequence number it applied.
+    pub fn feed(
+        &self, source: &mut Source, from: S,
+    ) -> impl Iterator<Item = (S, HashMapUpdate<Key, Value>)> + '_ {
+        // a feed from ahead of the current seq is empty rather than reversed
+        source
+            .scan(from.min(self.current_seq), self.current_seq)
+            .flat_map(move |(seq, event)| {
+                self.assignments(event)
+                    .into_iter()
+                    .map(move |update| (seq, update))
+            })
+    }
+
+    /// Returns how many updates `to_assignment` produces for the events between the given sequences, e.g. to estimate
+    /// the cost of building the index.
+    pub fn assignment_stats(
+        &self, source: &mut Source, start_exclusive: S, end_inclusive: S,
+    ) -> AssignmentStats {
+        let mut result = AssignmentStats::default();
+        for (_, event) in source.scan(start_exclusive, end_inclusive) {
+            let mut fan_out = 0;
+            (self.to_assignment)(event, &mut |_| fan_out += 1);
+            result.events += 1;
+            result.updates += fan_out;
+            result.max_fan_out = result.max_fan_out.max(fan_out);
+        }
+        result
+    }
+
+    /// Returns the full map at `seq`.
+    pub fn get_all(&self, source: &mut Source, seq: S) -> HashMap<Key, Value> {
+        if seq >= self.current_seq {
+            // read ahead of current sequence: apply un-applied updates to clone of current state
+            self.replay(source, self.map.clone(), self.current_seq, seq)
+        } else if let Some((&snapshot_seq, snapshot)) =
+            self.snapshots.maps.range(..=seq).next_back()
+        {
+            // read behind current sequence from a snapshot: apply updates since the snapshot to a clone of it
+            self.replay(source, snapshot.clone(), snapshot_seq, seq)
         } else {
             // read behind current sequence: rewind updates from current state
             let mut modified_keys = HashSet::new();
@@ -189,39 +632,35 @@ where
             // determine which keys have changed since the state we're reading at
             // if the map was cleared, that means all keys have been modified, even ones not in the current map
             for (_, event) in source.scan(seq, self.current_seq) {
-                for update in (self.to_assignment)(event) {
-                    match update {
-                        HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
-                            modified_keys.insert(key);
-                        }
-                        HashMapUpdate::Clear => {
-                            cleared = true;
-                            break;
-                        }
+                (self.to_assignment)(event, &mut |update| match update {
+                    HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
+                        modified_keys.insert(key);
                     }
-                }
+                    HashMapUpdate::Clear => {
+                        cleared = true;
+                    }
+                });
             }
 
             if cleared {
-                // if the state was cleared since seq, rebuild it from the most recent clear before seq
-                let mut removed_keys = HashSet::new();
+                // if the state was cleared since seq, rebuild it from the most recent clear at or before seq
+                // reading backwards, only the first update seen for each key (its most recent) counts
+                let mut decided_keys = HashSet::new();
                 let mut result = HashMap::new();
-                for (_, event) in source.scan(0, seq).rev() {
-                    for update in (self.to_assignment)(event).into_iter().rev() {
+                for (_, event) in source.scan(S::MIN, seq).rev() {
+                    for update in self.assignments(event).into_iter().rev() {
                         match update {
                             HashMapUpdate::Clear => {
-                                // this is the most recent clear, the one we needed to rebuild from
-                                break;
+                                // this is the most recent clear; every key not yet decided was absent at seq
+                                return result;
                             }
                             HashMapUpdate::Insert { key, value } => {
-                                // only the most recent insert counts, and only if it wasn't removed after
-                                if !result.contains_key(&key) && !removed_keys.contains(&key) {
+                                if decided_keys.insert(key.clone()) {
                                     result.insert(key, value);
                                 }
                             }
                             HashMapUpdate::Remove { key } => {
-                                // note removed keys so they're not inserted if the removal happened after the insertion
-                                removed_keys.insert(key);
+                                decided_keys.insert(key);
                             }
                         }
                     }
@@ -230,14 +669,15 @@ where
             } else {
                 // otherwise, look back from seq for the most recent modification to each modified key
                 let mut result = self.map.clone();
-                for (_, event) in source.scan(0, seq).rev() {
-                    for update in (self.to_assignment)(event).into_iter().rev() {
+                for (_, event) in source.scan(S::MIN, seq).rev() {
+                    for update in self.assignments(event).into_iter().rev() {
                         match update {
                             HashMapUpdate::Clear => {
                                 // remaining keys not inserted between this clear and seq
                                 for key in &modified_keys {
                                     result.remove(key);
                                 }
+                                return result;
                             }
                             HashMapUpdate::Insert { key, value } => {
                                 // only the most recent insert counts, and only if it wasn't removed more recently
@@ -246,8 +686,10 @@ where
                                 }
                             }
                             HashMapUpdate::Remove { key } => {
-                                // note removed keys so they're not inserted if the removal happened after the insertion
-                                modified_keys.remove(&key);
+                                // only the most recent removal counts, and only if it wasn't inserted more recently
+                                if modified_keys.remove(&key) {
+                                    result.remove(&key);
+                                }
                             }
                         }
                     }
@@ -268,78 +710,427 @@ where
             }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{HashMapIndex, HashMapUpdate};
-    use crate::{Index, Table, View};
-    use std::collections::HashMap;
-    use std::hash::Hash;
-
-    use crate::table::vec::VecTable;
+    /// Returns the number of keys in the map at the index's current sequence number.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
 
-    fn tuple_to_insert<Key: Clone + Eq + Hash, Value: Clone>(
-        kvp: (Key, Value),
-    ) -> Vec<HashMapUpdate<Key, Value>> {
-        let (key, value) = kvp.clone();
-        vec![HashMapUpdate::Insert { key, value }]
+    /// Returns whether the map is empty at the index's current sequence number.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
     }
 
-    #[test]
-    fn get_all() {
-        let mut table = VecTable::<(&str, &str)>::new();
+    /// Returns the number of keys in the map at `seq`, tracking which keys are present rather than reconstructing their
+    /// values.
+    pub fn len_at(&self, source: &mut Source, seq: S) -> usize {
+        if seq >= self.current_seq {
+            // read ahead of current sequence: apply un-applied updates to the current key set
+            let mut added_keys = HashSet::new();
+            let mut removed_keys = HashSet::new();
+            let mut cleared = false;
+            for (_, event) in source.scan(self.current_seq, seq) {
+                (self.to_assignment)(event, &mut |update| match update {
+                    HashMapUpdate::Insert { key, .. } => {
+                        removed_keys.remove(&key);
+                        if cleared || !self.map.contains_key(&key) {
+                            added_keys.insert(key);
+                        }
+                    }
+                    HashMapUpdate::Remove { key } => {
+                        added_keys.remove(&key);
+                        if !cleared && self.map.contains_key(&key) {
+                            removed_keys.insert(key);
+                        }
+                    }
+                    HashMapUpdate::Clear => {
+                        cleared = true;
+                        added_keys.clear();
+                        removed_keys.clear();
+                    }
+                });
+            }
+            if cleared {
+                added_keys.len()
+            } else {
+                self.map.len() + added_keys.len() - removed_keys.len()
+            }
+        } else {
+            // read behind current sequence: determine which keys have changed since the state we're reading at
+            let mut modified_keys = HashSet::new();
+            let mut cleared = false;
+            for (_, event) in source.scan(seq, self.current_seq) {
+                (self.to_assignment)(event, &mut |update| match update {
+                    HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
+                        modified_keys.insert(key);
+                    }
+                    HashMapUpdate::Clear => {
+                        cleared = true;
+                    }
+                });
+            }
 
-        let current_seq = {
-            table.append([
-                ("key1", "value1"),
-                ("key2", "value2"),
-                ("key3", "value3"),
-                ("key4", "value4"),
-            ]);
-            table.get_current_seq()
-        };
+            // if the state was cleared since seq, count from the most recent clear at or before seq; otherwise, count
+            // from the current state, adjusting for each modified key's presence at seq
+            let mut len = if cleared { 0 } else { self.map.len() };
+            let mut decided_keys = HashSet::new();
+            let present_now = |key: &Key| !cleared && self.map.contains_key(key);
+            'scan: for (_, event) in source.scan(S::MIN, seq).rev() {
+                for update in self.assignments(event).into_iter().rev() {
+                    let (key, present) = match update {
+                        HashMapUpdate::Clear => break 'scan,
+                        HashMapUpdate::Insert { key, .. } => (key, true),
+                        HashMapUpdate::Remove { key } => (key, false),
+                    };
+                    // only the most recent modification to each key counts
+                    if (cleared || modified_keys.contains(&key)) && decided_keys.insert(key.clone())
+                    {
+                        match (present_now(&key), present) {
+                            (false, true) => len += 1,
+                            (true, false) => len -= 1,
+                            _ => {}
+                        }
+                    }
+                }
 
-        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
-        hash_map_index.update(&mut table, current_seq);
+                // once we find all modified keys, we're done
+                if !cleared && decided_keys.len() == modified_keys.len() {
+                    return len;
+                }
+            }
 
-        assert_eq!(current_seq, 4);
-        assert_eq!(hash_map_index.get_current_seq(), 4);
+            // remaining keys were absent at seq
+            for key in modified_keys.difference(&decided_keys) {
+                if present_now(key) {
+                    len -= 1;
+                }
+            }
+            len
+        }
+    }
+}
 
-        assert_eq!(hash_map_index.get_all(&mut table, 0), HashMap::from_iter(vec![].into_iter()));
-        assert_eq!(
-            hash_map_index.get_all(&mut table, 1),
-            HashMap::from_iter(vec![("key1", "value1")].into_iter())
-        );
-        assert_eq!(
-            hash_map_index.get_all(&mut table, 2),
-            HashMap::from_iter(vec![("key1", "value1"), ("key2", "value2")].into_iter())
-        );
-        assert_eq!(
-            hash_map_index.get_all(&mut table, 3),
-            HashMap::from_iter(
-                vec![("key1", "value1"), ("key2", "value2"), ("key3", "value3")].into_iter()
-            )
-        );
-        assert_eq!(
-            hash_map_index.get_all(&mut table, 4),
-            HashMap::from_iter(
-                vec![
-                    ("key1", "value1"),
-                    ("key2", "value2"),
-                    ("key3", "value3"),
-                    ("key4", "value4")
-                ]
-                .into_iter()
-            )
+impl<Source, Key, Value, S> HashMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    /// Applies an event's updates to the map and advances the current seq to the event's, as each of the `update`
+    /// variants does for every scanned event. `before_update` sees each update along with the map it's about to apply to,
+    /// e.g. to record the values it overwrites.
+    fn apply_event(
+        &mut self, seq: S, event: Source::Event,
+        mut before_update: impl FnMut(&HashMap<Key, Value>, &HashMapUpdate<Key, Value>),
+    ) {
+        // catch broken views at their point of use; equal seqs are allowed because composite views interleave nodes
+        debug_assert!(
+            seq >= self.current_seq,
+            "source scanned seq {:?} after seq {:?}",
+            seq,
+            self.current_seq
         );
+        self.current_seq = seq;
+        self.first_consumed_seq.get_or_insert(seq);
+        self.snapshots.before_event(Some(seq), &self.map);
+        let map = &mut self.map;
+        let on_update = &mut self.on_update;
+        (self.to_assignment)(event, &mut |update| {
+            if let Some(on_update) = on_update {
+                on_update(seq, &update);
+            }
+            before_update(map, &update);
+            match update {
+                HashMapUpdate::Insert { key, value } => {
+                    map.insert(key, value);
+                }
+                HashMapUpdate::Remove { key } => {
+                    map.remove(&key);
+                }
+                HashMapUpdate::Clear => {
+                    map.clear();
+                }
+            }
+        });
+        self.snapshots.after_event(seq);
     }
 
-    #[test]
-    fn get_all_overwrite() {
-        let mut table = VecTable::<(&str, &str)>::new();
+    /// Applies the updates of the events between `start_exclusive` and `end_inclusive` to `map`.
+    fn replay(
+        &self, source: &mut Source, mut map: HashMap<Key, Value>, start_exclusive: S,
+        end_inclusive: S,
+    ) -> HashMap<Key, Value> {
+        for (_, event) in source.scan(start_exclusive, end_inclusive) {
+            (self.to_assignment)(event, &mut |update| match update {
+                HashMapUpdate::Insert { key, value } => {
+                    map.insert(key, value);
+                }
+                HashMapUpdate::Remove { key } => {
+                    map.remove(&key);
+                }
+                HashMapUpdate::Clear => {
+                    map.clear();
+                }
+            });
+        }
+        map
+    }
+}
 
-        let current_seq = {
+#[cfg(feature = "serde_json")]
+mod json_impl {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+    use std::io::Write;
+
+    use serde::{Serialize, Serializer};
+
+    use super::HashMapIndex;
+    use crate::{SeqType, View};
+
+    /// How `HashMapIndex::to_json` lays out the map.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum JsonLayout {
+        /// A JSON object, e.g. `{"key":"value"}`. Fails for keys that don't serialize to strings or numbers, e.g. tuples.
+        Object,
+
+        /// A JSON array of `[key, value]` pairs, e.g. `[["key","value"]]`, which works for any key.
+        Pairs,
+    }
+
+    struct Pairs<'a, Key, Value>(&'a HashMap<Key, Value>);
+
+    impl<Key: Serialize, Value: Serialize> Serialize for Pairs<'_, Key, Value> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter())
+        }
+    }
+
+    impl<Source, Key, Value, S> HashMapIndex<Source, Key, Value, S>
+    where
+        Source: View<S>,
+        S: SeqType,
+        Key: Clone + Eq + Hash + Serialize,
+        Value: Clone + Serialize,
+    {
+        /// Returns the map at `seq` as JSON, e.g. for debugging or dashboards.
+        pub fn to_json(
+            &self, source: &mut Source, seq: S, layout: JsonLayout,
+        ) -> serde_json::Result<String> {
+            let mut result = Vec::new();
+            self.write_json(source, seq, layout, &mut result)?;
+            Ok(String::from_utf8(result).expect("serde_json writes UTF-8"))
+        }
+
+        /// Like `to_json`, but writes the JSON to `w` rather than building a string.
+        pub fn write_json(
+            &self, source: &mut Source, seq: S, layout: JsonLayout, w: impl Write,
+        ) -> serde_json::Result<()> {
+            let map = self.get_all(source, seq);
+            match layout {
+                JsonLayout::Object => serde_json::to_writer(w, &map),
+                JsonLayout::Pairs => serde_json::to_writer(w, &Pairs(&map)),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::JsonLayout;
+        use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+        use crate::table::vec::VecTable;
+        use crate::{Index, Table};
+        use std::collections::HashMap;
+
+        #[test]
+        fn object() {
+            let mut table = VecTable::<(&str, u64)>::new();
+            table.append([("a", 1), ("b", 2), ("a", 3)]);
+            let mut index =
+                HashMapIndex::new(|(key, value)| [HashMapUpdate::Insert { key, value }]);
+            index.update(&mut table, 3);
+
+            let json = index.to_json(&mut table, 2, JsonLayout::Object).unwrap();
+            assert_eq!(
+                serde_json::from_str::<HashMap<String, u64>>(&json).unwrap(),
+                HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+            );
+
+            let mut written = Vec::new();
+            index
+                .write_json(&mut table, 3, JsonLayout::Object, &mut written)
+                .unwrap();
+            assert_eq!(
+                serde_json::from_slice::<HashMap<String, u64>>(&written).unwrap(),
+                HashMap::from([("a".to_string(), 3), ("b".to_string(), 2)])
+            );
+        }
+
+        #[test]
+        fn pairs() {
+            let mut table = VecTable::<((u64, u64), &str)>::new();
+            table.append([((1, 2), "a"), ((3, 4), "b")]);
+            let mut index =
+                HashMapIndex::new(|(key, value)| [HashMapUpdate::Insert { key, value }]);
+            index.update(&mut table, 2);
+
+            // tuple keys can't be object keys
+            assert!(index.to_json(&mut table, 2, JsonLayout::Object).is_err());
+
+            let json = index.to_json(&mut table, 2, JsonLayout::Pairs).unwrap();
+            let mut pairs = serde_json::from_str::<Vec<((u64, u64), String)>>(&json).unwrap();
+            pairs.sort();
+            assert_eq!(pairs, vec![((1, 2), "a".to_string()), ((3, 4), "b".to_string())]);
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+pub use json_impl::JsonLayout;
+
+#[cfg(feature = "csv")]
+mod csv_impl {
+    use std::fmt::Display;
+    use std::hash::Hash;
+    use std::io::{self, Write};
+
+    use super::HashMapIndex;
+    use crate::{SeqType, View};
+
+    /// Writes a field, quoting it per RFC 4180 if it contains a comma, quote, or line break.
+    fn write_field(w: &mut impl Write, field: &str) -> io::Result<()> {
+        if field.contains([',', '"', '\r', '\n']) {
+            write!(w, "\"{}\"", field.replace('"', "\"\""))
+        } else {
+            w.write_all(field.as_bytes())
+        }
+    }
+
+    impl<Source, Key, Value, S> HashMapIndex<Source, Key, Value, S>
+    where
+        Source: View<S>,
+        S: SeqType,
+        Key: Clone + Eq + Hash + Ord + Display,
+        Value: Clone + Display,
+    {
+        /// Writes the map at `seq` as CSV: a `key,value` header, then one row per entry sorted by key, so snapshots at
+        /// different seqs diff cleanly. Rows end with CRLF, per RFC 4180.
+        pub fn write_csv(&self, source: &mut Source, seq: S, mut w: impl Write) -> io::Result<()> {
+            let mut entries = self.get_all(source, seq).into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            w.write_all(b"key,value\r\n")?;
+            for (key, value) in entries {
+                write_field(&mut w, &key.to_string())?;
+                w.write_all(b",")?;
+                write_field(&mut w, &value.to_string())?;
+                w.write_all(b"\r\n")?;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+        use crate::table::vec::VecTable;
+        use crate::{Index, Table};
+
+        #[test]
+        fn write_csv() {
+            let mut table = VecTable::<(&str, &str)>::new();
+            table.append([("b", "x, y"), ("a", "say \"hi\""), ("c", "plain"), ("d", "gone")]);
+            let mut index =
+                HashMapIndex::new(|(key, value)| [HashMapUpdate::Insert { key, value }]);
+            index.update(&mut table, 4);
+
+            let mut written = Vec::new();
+            index.write_csv(&mut table, 3, &mut written).unwrap();
+            assert_eq!(
+                String::from_utf8(written).unwrap(),
+                "key,value\r\na,\"say \"\"hi\"\"\"\r\nb,\"x, y\"\r\nc,plain\r\n"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssignmentStats, HashMapIndex, HashMapUpdate, KeyState};
+    use crate::index::diff::diff_maps;
+    use crate::{Index, Seq, Table, View};
+    use std::collections::{HashMap, HashSet};
+    use std::hash::Hash;
+    use std::iter;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use crate::table::vec::VecTable;
+    use crate::testing::fixtures::{CountingView, Counts};
+
+    fn tuple_to_insert<Key: Clone + Eq + Hash, Value: Clone>(
+        kvp: (Key, Value),
+    ) -> iter::Once<HashMapUpdate<Key, Value>> {
+        let (key, value) = kvp.clone();
+        iter::once(HashMapUpdate::Insert { key, value })
+    }
+
+    #[test]
+    fn get_all() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key3", "value3"),
+                ("key4", "value4"),
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, current_seq);
+
+        assert_eq!(current_seq, 4);
+        assert_eq!(hash_map_index.get_current_seq(), 4);
+
+        assert_eq!(hash_map_index.get_all(&mut table, 0), HashMap::from_iter(vec![].into_iter()));
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 1),
+            HashMap::from_iter(vec![("key1", "value1")].into_iter())
+        );
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 2),
+            HashMap::from_iter(vec![("key1", "value1"), ("key2", "value2")].into_iter())
+        );
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 3),
+            HashMap::from_iter(
+                vec![("key1", "value1"), ("key2", "value2"), ("key3", "value3")].into_iter()
+            )
+        );
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 4),
+            HashMap::from_iter(
+                vec![
+                    ("key1", "value1"),
+                    ("key2", "value2"),
+                    ("key3", "value3"),
+                    ("key4", "value4")
+                ]
+                .into_iter()
+            )
+        );
+    }
+
+    #[test]
+    fn get_all_overwrite() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
             table.append([
                 ("key1", "value1"),
                 ("key2", "value2"),
@@ -378,6 +1169,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_all_removed() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            HashMapUpdate::Remove { key: "key1" },
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+        ]);
+
+        let mut hash_map_index = HashMapIndex::new(|update: HashMapUpdate<_, _>| [update]);
+        hash_map_index.update(&mut table, 4);
+
+        // reading behind a reinsertion, the key's most recent modification is its removal, so it's absent
+        assert_eq!(hash_map_index.get_all(&mut table, 3), HashMap::from([("key2", "value2")]));
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 2),
+            HashMap::from([("key1", "value1"), ("key2", "value2")])
+        );
+    }
+
     #[test]
     fn get_all_clear() {
         let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
@@ -415,40 +1227,879 @@ mod tests {
         );
     }
 
-    // todo: something is broken with clear
-    // #[test]
-    // fn get_all_clear_multiple_modifications() {
-    //     let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
-
-    //     let current_seq = {
-    //         table.write([
-    //             HashMapUpdate::Insert { key: "key1", value: "value1" },
-    //             HashMapUpdate::Clear,
-    //             HashMapUpdate::Insert { key: "key1", value: "value1" },
-    //             HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
-    //         ]);
-    //         table.next_seq()
-    //     };
-
-    //     let mut hash_map_index = HashMapIndex::new(&table, |assignment| vec![assignment.clone()]);
-    //     hash_map_index.update(current_seq);
-
-    //     assert_eq!(current_seq, 4);
-    //     assert_eq!(hash_map_index.current_seq(), 4);
-
-    //     assert_eq!(hash_map_index.get_all(&mut table, 0), HashMap::from_iter(vec![].into_iter()));
-    //     assert_eq!(
-    //         hash_map_index.get_all(&mut table, 1),
-    //         HashMap::from_iter(vec![("key1", "value1")].into_iter())
-    //     );
-    //     assert_eq!(hash_map_index.get_all(&mut table, 2), HashMap::from_iter(vec![].into_iter()));
-    //     assert_eq!(
-    //         hash_map_index.get_all(&mut table, 3),
-    //         HashMap::from_iter(vec![("key1", "value1")].into_iter())
-    //     );
-    //     assert_eq!(
-    //         hash_map_index.get_all(&mut table, 4),
-    //         HashMap::from_iter(vec![("key1", "VALUE1")].into_iter())
-    //     );
-    // }
+    #[test]
+    fn get_previous() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key1", "VALUE1"),
+                ("key2", "VALUE2"),
+                ("key1", "Value1"),
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, current_seq);
+
+        assert_eq!(hash_map_index.get_previous(&mut table, &"key1"), Some((3, "VALUE1")));
+        assert_eq!(hash_map_index.get_previous(&mut table, &"key2"), Some((2, "value2")));
+        assert_eq!(hash_map_index.get_previous(&mut table, &"key3"), None);
+    }
+
+    #[test]
+    fn get_previous_set_once() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([("key1", "value1"), ("key2", "value2")]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, current_seq);
+
+        assert_eq!(hash_map_index.get_previous(&mut table, &"key1"), None);
+    }
+
+    #[test]
+    fn resume() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([
+            ("key1", "value1"),
+            ("key2", "value2"),
+            ("key3", "value3"),
+            ("key4", "value4"),
+            ("key5", "value5"),
+        ]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 5);
+        let snapshot = hash_map_index.snapshot();
+        assert_eq!(snapshot.seq, 5);
+
+        table.append([("key1", "VALUE1"), ("key6", "value6"), ("key2", "VALUE2")]);
+
+        let resumed = HashMapIndex::resume(snapshot, &mut table, tuple_to_insert);
+        assert_eq!(resumed.get_current_seq(), 8);
+
+        let mut fresh = HashMapIndex::new(tuple_to_insert);
+        fresh.update(&mut table, 8);
+        assert_eq!(resumed.get_all(&mut table, 8), fresh.get_all(&mut table, 8));
+        assert_eq!(
+            resumed.get_all(&mut table, 8),
+            HashMap::from_iter(
+                vec![
+                    ("key1", "VALUE1"),
+                    ("key2", "VALUE2"),
+                    ("key3", "value3"),
+                    ("key4", "value4"),
+                    ("key5", "value5"),
+                    ("key6", "value6")
+                ]
+                .into_iter()
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn resume_serialized() {
+        let mut table = VecTable::<(String, String)>::new();
+        table.append((0..10).map(|i| (format!("key{}", i % 4), format!("value{}", i))));
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 6);
+        let json = serde_json::to_string(&hash_map_index.snapshot()).unwrap();
+
+        let snapshot = serde_json::from_str(&json).unwrap();
+        let resumed = HashMapIndex::resume(snapshot, &mut table, tuple_to_insert);
+        assert_eq!(resumed.get_current_seq(), 10);
+
+        let mut fresh = HashMapIndex::new(tuple_to_insert);
+        fresh.update(&mut table, 10);
+        assert_eq!(resumed.get_all(&mut table, 10), fresh.get_all(&mut table, 10));
+        for i in 0..4 {
+            let key = format!("key{}", i);
+            assert_eq!(resumed.get(&mut table, 10, &key), fresh.get(&mut table, 10, &key));
+            assert_eq!(resumed.get(&mut table, 8, &key), fresh.get(&mut table, 8, &key));
+        }
+    }
+
+    #[test]
+    fn from_checkpoint() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([("key1", "value1"), ("key2", "value2")]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 2);
+        let checkpoint = hash_map_index.checkpoint();
+
+        table.append([("key1", "VALUE1"), ("key3", "value3")]);
+        let mut restored = HashMapIndex::from_checkpoint(tuple_to_insert, checkpoint);
+        restored.update(&mut table, 4);
+        assert_eq!(
+            restored.get_all(&mut table, 4),
+            HashMap::from([("key1", "VALUE1"), ("key2", "value2"), ("key3", "value3")])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn from_checkpoint_serialized() {
+        let mut table = VecTable::<(String, String)>::new();
+        table.append((0..10).map(|i| (format!("key{}", i % 4), format!("value{}", i))));
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 6);
+        let json = serde_json::to_string(&hash_map_index.checkpoint()).unwrap();
+
+        let mut restored =
+            HashMapIndex::from_checkpoint(tuple_to_insert, serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.get_current_seq(), 6);
+        restored.update(&mut table, 10);
+
+        let mut fresh = HashMapIndex::new(tuple_to_insert);
+        fresh.update(&mut table, 10);
+        assert_eq!(restored.get_all(&mut table, 10), fresh.get_all(&mut table, 10));
+        for i in 0..4 {
+            let key = format!("key{}", i);
+            assert_eq!(restored.get(&mut table, 10, &key), fresh.get(&mut table, 10, &key));
+            assert_eq!(restored.get(&mut table, 8, &key), fresh.get(&mut table, 8, &key));
+        }
+    }
+
+    #[test]
+    fn get_max_backward_scan() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let current_seq = {
+            table.append([
+                ("key1", "value1"),
+                ("key2", "value2"),
+                ("key2", "VALUE2"),
+                ("key2", "Value2"),
+                ("key1", "VALUE1"),
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut unlimited = HashMapIndex::new(tuple_to_insert);
+        unlimited.update(&mut table, current_seq);
+
+        // a limit of one forces the fallback when reading key1 at seqs 2-4, whose most recent modification is seq 1
+        let mut limited = HashMapIndex::new_with_max_backward_scan(tuple_to_insert, Some(1));
+        limited.update(&mut table, current_seq);
+
+        for seq in 0..=current_seq {
+            for key in ["key1", "key2", "key3"] {
+                assert_eq!(
+                    limited.get(&mut table, seq, &key),
+                    unlimited.get(&mut table, seq, &key),
+                    "seq {} key {}",
+                    seq,
+                    key
+                );
+            }
+        }
+        assert_eq!(limited.get(&mut table, 3, &"key1"), Some("value1"));
+        assert_eq!(limited.get(&mut table, 0, &"key1"), None);
+    }
+
+    #[test]
+    fn u128_seqs() {
+        let base = u64::MAX as u128;
+        let mut table = VecTable::<(&str, &str), u128>::default();
+        table.set_current_seq(base);
+        table.append([("key1", "value1"), ("key2", "value2"), ("key1", "VALUE1")]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, base + 2);
+        assert_eq!(hash_map_index.get_current_seq(), base + 2);
+
+        // behind, at, and ahead of the current seq
+        assert_eq!(hash_map_index.get(&mut table, base + 1, &"key1"), Some("value1"));
+        assert_eq!(hash_map_index.get(&mut table, base + 2, &"key2"), Some("value2"));
+        assert_eq!(hash_map_index.get(&mut table, base + 3, &"key1"), Some("VALUE1"));
+        assert_eq!(hash_map_index.get_all(&mut table, base), HashMap::new());
+        assert_eq!(hash_map_index.len_at(&mut table, base + 1), 1);
+        assert_eq!(hash_map_index.snapshot().seq, base + 2);
+    }
+
+    #[test]
+    fn get_max_backward_scan_fallback() {
+        let counts = Rc::new(Counts::default());
+        let mut source = CountingView::new(Rc::clone(&counts));
+        source.table.append(
+            iter::once(("key1", 1))
+                .chain((2..=21).map(|value| ("key2", value)))
+                .chain(iter::once(("key1", 22))),
+        );
+
+        let mut unlimited = HashMapIndex::new(tuple_to_insert);
+        unlimited.update(&mut source, 22);
+        let mut limited = HashMapIndex::new_with_max_backward_scan(tuple_to_insert, Some(2));
+        limited.update(&mut source, 22);
+
+        // key1 was modified after seq 21, so get reads back from 21 until its insert at seq 1, one event per seq
+        counts.reset();
+        assert_eq!(unlimited.get(&mut source, 21, &"key1"), Some(1));
+        assert_eq!((counts.scans.get(), counts.events.get()), (2, 1 + 21));
+
+        // the limited index gives up after pulling 3 events, then scans twice more computing the full map at seq 21
+        counts.reset();
+        assert_eq!(limited.get(&mut source, 21, &"key1"), Some(1));
+        assert_eq!((counts.scans.get(), counts.events.get()), (4, 1 + 3 + 1 + 21));
+
+        // within the limit, there's no fallback
+        counts.reset();
+        assert_eq!(limited.get(&mut source, 20, &"key2"), Some(20));
+        assert_eq!((counts.scans.get(), counts.events.get()), (2, 2 + 1));
+    }
+
+    #[test]
+    fn changed_keys() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+
+        let current_seq = {
+            table.append([
+                HashMapUpdate::Insert { key: "key1", value: "value1" },
+                HashMapUpdate::Insert { key: "key2", value: "value2" },
+                HashMapUpdate::Remove { key: "key1" },
+                HashMapUpdate::Insert { key: "key3", value: "value3" },
+                HashMapUpdate::Clear,
+                HashMapUpdate::Insert { key: "key4", value: "value4" },
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index =
+            HashMapIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+        hash_map_index.update(&mut table, current_seq);
+
+        assert_eq!(hash_map_index.changed_keys(&mut table, 0, 0), (HashSet::new(), false));
+        assert_eq!(
+            hash_map_index.changed_keys(&mut table, 1, 4),
+            (HashSet::from(["key1", "key2", "key3"]), false)
+        );
+        assert_eq!(
+            hash_map_index.changed_keys(&mut table, 3, 6),
+            (HashSet::from(["key3", "key4"]), true)
+        );
+    }
+
+    #[test]
+    fn apply_changeset() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([("key1", "value1"), ("key2", "value2"), ("key3", "value3")]);
+        table.append([("key2", "VALUE2"), ("key4", "value4")]);
+
+        let mut local = HashMapIndex::new(tuple_to_insert);
+        local.update(&mut table, 5);
+        let changeset = diff_maps(&local.get_all(&mut table, 3), &local.get_all(&mut table, 5));
+
+        // the remote index has no events past seq 3, only the changeset
+        let mut remote_table = VecTable::<(&str, &str)>::new();
+        remote_table.append([("key1", "value1"), ("key2", "value2"), ("key3", "value3")]);
+        let mut remote = HashMapIndex::new(tuple_to_insert);
+        remote.update(&mut remote_table, 3);
+
+        remote.apply_changeset(&changeset, 5);
+        assert_eq!(remote.get_current_seq(), 5);
+        assert_eq!(remote.get_all(&mut remote_table, 5), local.get_all(&mut table, 5));
+    }
+
+    #[test]
+    fn lag() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([
+            ("key1", "value1"),
+            ("key2", "value2"),
+            ("key3", "value3"),
+            ("key4", "value4"),
+            ("key5", "value5"),
+        ]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        assert_eq!(hash_map_index.lag(&mut table), 5);
+
+        hash_map_index.update(&mut table, 5);
+        assert_eq!(hash_map_index.lag(&mut table), 0);
+
+        table.append([("key6", "value6"), ("key7", "value7"), ("key8", "value8")]);
+        assert_eq!(hash_map_index.lag(&mut table), 3);
+
+        hash_map_index.update(&mut table, 8);
+        assert_eq!(hash_map_index.lag(&mut table), 0);
+    }
+
+    #[test]
+    fn assignment_stats() {
+        let mut table = VecTable::<Vec<(&str, &str)>>::new();
+        table.append([
+            vec![("key1", "value1")],
+            vec![],
+            vec![("key2", "value2"), ("key3", "value3"), ("key4", "value4")],
+            vec![("key1", "VALUE1"), ("key2", "VALUE2")],
+        ]);
+
+        let hash_map_index = HashMapIndex::new(|kvps: Vec<(&'static str, &'static str)>| {
+            kvps.into_iter()
+                .map(|(key, value)| HashMapUpdate::Insert { key, value })
+        });
+
+        assert_eq!(
+            hash_map_index.assignment_stats(&mut table, 0, 4),
+            AssignmentStats { events: 4, updates: 6, max_fan_out: 3 }
+        );
+        assert_eq!(
+            hash_map_index.assignment_stats(&mut table, 3, 4),
+            AssignmentStats { events: 1, updates: 2, max_fan_out: 2 }
+        );
+        assert_eq!(hash_map_index.assignment_stats(&mut table, 4, 4), AssignmentStats::default());
+    }
+
+    #[test]
+    fn update_cancellable() {
+        let mut table = VecTable::<(u64, u64)>::new();
+        table.append((1..=100).map(|i| (i, i)));
+
+        // request cancellation partway through the update
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let mut hash_map_index = HashMapIndex::new({
+            let should_stop = should_stop.clone();
+            move |(key, value): (u64, u64)| {
+                if key == 20 {
+                    should_stop.store(true, Ordering::Relaxed);
+                }
+                vec![HashMapUpdate::Insert { key, value }]
+            }
+        });
+
+        let reached = hash_map_index.update_cancellable(&mut table, 100, &should_stop);
+        assert!((20..100).contains(&reached));
+        assert_eq!(hash_map_index.get_current_seq(), reached);
+        assert_eq!(
+            hash_map_index.get_all(&mut table, reached),
+            HashMap::from_iter((1..=reached).map(|i| (i, i)))
+        );
+
+        // a later update picks up where the cancelled one left off
+        should_stop.store(false, Ordering::Relaxed);
+        assert_eq!(hash_map_index.update_cancellable(&mut table, 100, &should_stop), 100);
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 100),
+            HashMap::from_iter((1..=100).map(|i| (i, i)))
+        );
+    }
+
+    #[test]
+    fn with_initial() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        // the seeded state stands in for seqs 1-2, which have no events
+        table.set_current_seq(2);
+        let current_seq = {
+            table.append([("key2", "VALUE2"), ("key3", "value3")]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index = HashMapIndex::with_initial(
+            tuple_to_insert,
+            HashMap::from([("key1", "value1"), ("key2", "value2")]),
+            2,
+        );
+        assert_eq!(hash_map_index.get_current_seq(), 2);
+        assert_eq!(hash_map_index.get(&mut table, 3, &"key1"), Some("value1"));
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 3),
+            HashMap::from([("key1", "value1"), ("key2", "VALUE2")])
+        );
+
+        hash_map_index.update(&mut table, current_seq);
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 4),
+            HashMap::from([("key1", "value1"), ("key2", "VALUE2"), ("key3", "value3")])
+        );
+        assert_eq!(hash_map_index.get(&mut table, 4, &"key2"), Some("VALUE2"));
+    }
+
+    #[test]
+    fn covered_range() {
+        let mut table = VecTable::<(&str, &str)>::new();
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        assert_eq!(hash_map_index.covered_range(), (0, 0));
+
+        table.append([("key1", "value1"), ("key2", "value2"), ("key3", "value3")]);
+        hash_map_index.update(&mut table, 2);
+        assert_eq!(hash_map_index.covered_range(), (1, 2));
+        hash_map_index.update(&mut table, 3);
+        assert_eq!(hash_map_index.covered_range(), (1, 3));
+        assert!(table.first_seq().unwrap() <= hash_map_index.covered_range().0);
+
+        // the same events, with the first two trimmed
+        let mut trimmed = VecTable::<(&str, &str)>::new();
+        trimmed.set_current_seq(2);
+        trimmed.append([("key3", "value3")]);
+        assert!(trimmed.first_seq().unwrap() > hash_map_index.covered_range().0);
+    }
+
+    /// Scans its events in the order given, regardless of their sequence numbers.
+    struct UnorderedView(Vec<(Seq, (&'static str, &'static str))>);
+
+    impl View for UnorderedView {
+        type Event = (&'static str, &'static str);
+        type Iterator = std::vec::IntoIter<(Seq, Self::Event)>;
+
+        fn scan(&mut self, _: Seq, _: Seq) -> Self::Iterator {
+            self.0.clone().into_iter()
+        }
+
+        fn get_current_seq(&mut self) -> Seq {
+            self.0.iter().map(|(seq, _)| *seq).max().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn update_backward() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([("key1", "value1"), ("key2", "value2"), ("key1", "VALUE1")]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 3);
+        hash_map_index.update(&mut table, 1);
+        assert_eq!(hash_map_index.get_current_seq(), 3);
+        assert_eq!(hash_map_index.update_with_diff(&mut table, 2), vec![]);
+        assert_eq!(hash_map_index.update_cancellable(&mut table, 2, &AtomicBool::new(false)), 3);
+
+        assert_eq!(hash_map_index.get(&mut table, 3, &"key1"), Some("VALUE1"));
+        assert_eq!(hash_map_index.get(&mut table, 1, &"key1"), Some("value1"));
+        assert_eq!(hash_map_index.get(&mut table, 1, &"key2"), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "source scanned seq 2 after seq 3")]
+    fn update_non_monotonic() {
+        let mut view = UnorderedView(vec![
+            (1, ("key1", "value1")),
+            (3, ("key3", "value3")),
+            (2, ("key2", "value2")),
+        ]);
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut view, 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "source scanned seq 2 after seq 3")]
+    fn update_with_diff_non_monotonic() {
+        let mut view = UnorderedView(vec![
+            (1, ("key1", "value1")),
+            (3, ("key3", "value3")),
+            (2, ("key2", "value2")),
+        ]);
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update_with_diff(&mut view, 3);
+    }
+
+    #[test]
+    fn len_at() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+            HashMapUpdate::Remove { key: "key2" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key3", value: "value3" },
+            HashMapUpdate::Remove { key: "key4" },
+            HashMapUpdate::Insert { key: "key4", value: "value4" },
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Remove { key: "key3" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+        ]);
+
+        for current_seq in 0..=12 {
+            let mut hash_map_index =
+                HashMapIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+            hash_map_index.update(&mut table, current_seq);
+
+            let len = hash_map_index.get_all(&mut table, current_seq).len();
+            assert_eq!(hash_map_index.len(), len);
+            assert_eq!(hash_map_index.is_empty(), len == 0);
+            for seq in 0..=12 {
+                assert_eq!(
+                    hash_map_index.len_at(&mut table, seq),
+                    hash_map_index.get_all(&mut table, seq).len(),
+                    "current seq {}, seq {}",
+                    current_seq,
+                    seq
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn contains_key() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+            HashMapUpdate::Remove { key: "key2" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+        ]);
+
+        for current_seq in 0..=6 {
+            let mut hash_map_index =
+                HashMapIndex::new(|assignment: HashMapUpdate<_, _>| [assignment]);
+            hash_map_index.update(&mut table, current_seq);
+
+            for seq in 0..=6 {
+                let all = hash_map_index.get_all(&mut table, seq);
+                for key in ["key1", "key2", "key3"] {
+                    assert_eq!(
+                        hash_map_index.contains_key(&mut table, seq, &key),
+                        all.contains_key(key),
+                        "current seq {}, seq {}, key {}",
+                        current_seq,
+                        seq,
+                        key
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn update_with_diff() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            HashMapUpdate::Insert { key: "key3", value: "value3" },
+            // changed
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+            // inserted then removed
+            HashMapUpdate::Insert { key: "key4", value: "value4" },
+            HashMapUpdate::Remove { key: "key4" },
+            // changed and changed back
+            HashMapUpdate::Insert { key: "key2", value: "VALUE2" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            // removed
+            HashMapUpdate::Remove { key: "key3" },
+            // cleared then partially restored
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+            HashMapUpdate::Insert { key: "key5", value: "value5" },
+        ]);
+
+        let mut hash_map_index = HashMapIndex::new(|update: HashMapUpdate<_, _>| [update]);
+        let as_map = |diff: Vec<HashMapUpdate<&'static str, &'static str>>| {
+            diff.into_iter()
+                .map(|update| match update {
+                    HashMapUpdate::Insert { key, value } => (key, Some(value)),
+                    HashMapUpdate::Remove { key } => (key, None),
+                    HashMapUpdate::Clear => panic!("diff contains a clear"),
+                })
+                .collect::<HashMap<_, _>>()
+        };
+
+        let diff = hash_map_index.update_with_diff(&mut table, 3);
+        assert_eq!(diff.len(), 3);
+        assert_eq!(
+            as_map(diff),
+            HashMap::from([
+                ("key1", Some("value1")),
+                ("key2", Some("value2")),
+                ("key3", Some("value3"))
+            ])
+        );
+
+        let diff = hash_map_index.update_with_diff(&mut table, 9);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(as_map(diff), HashMap::from([("key1", Some("VALUE1")), ("key3", None)]));
+
+        let diff = hash_map_index.update_with_diff(&mut table, 12);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(as_map(diff), HashMap::from([("key2", None), ("key5", Some("value5"))]));
+        assert_eq!(hash_map_index.get_current_seq(), 12);
+
+        // the diff matches diffing the materialized maps
+        for (from, to) in [(0, 12), (2, 7), (5, 11)] {
+            let mut hash_map_index = HashMapIndex::new(|update: HashMapUpdate<_, _>| [update]);
+            hash_map_index.update(&mut table, from);
+            let before = hash_map_index.get_all(&mut table, from);
+            let after = hash_map_index.get_all(&mut table, to);
+            assert_eq!(
+                as_map(hash_map_index.update_with_diff(&mut table, to)),
+                as_map(diff_maps(&before, &after))
+            );
+        }
+    }
+
+    // closures are required to be `Send` so that indexes are too, with or without the `rayon` feature
+    #[test]
+    fn send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut hash_map_index = HashMapIndex::<VecTable<(&str, &str)>, _, _>::new(tuple_to_insert);
+        hash_map_index.on_update(|_, _| {});
+        assert_send(&hash_map_index);
+    }
+
+    #[test]
+    fn on_update() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Remove { key: "key2" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+        ]);
+
+        let mut hash_map_index = HashMapIndex::new(|update: HashMapUpdate<_, _>| [update]);
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        hash_map_index.on_update({
+            let applied = Arc::clone(&applied);
+            move |seq, update| applied.lock().unwrap().push((seq, update.clone()))
+        });
+        hash_map_index.update(&mut table, 2);
+        hash_map_index.update(&mut table, 4);
+
+        // reads replay events without applying them
+        hash_map_index.get(&mut table, 1, &"key1");
+        hash_map_index.get_all(&mut table, 2);
+
+        assert_eq!(
+            *applied.lock().unwrap(),
+            vec![
+                (1, HashMapUpdate::Insert { key: "key1", value: "value1" }),
+                (2, HashMapUpdate::Remove { key: "key2" }),
+                (3, HashMapUpdate::Clear),
+                (4, HashMapUpdate::Insert { key: "key2", value: "value2" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_state() {
+        let mut table = VecTable::<HashMapUpdate<u64, u64>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: 1, value: 12 },
+            HashMapUpdate::Insert { key: 2, value: 34 },
+            HashMapUpdate::Remove { key: 1 },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: 2, value: 56 },
+        ]);
+
+        for current_seq in [0, 3, 5] {
+            let mut index = HashMapIndex::new(|update: HashMapUpdate<u64, u64>| iter::once(update));
+            index.update(&mut table, current_seq);
+
+            assert_eq!(index.get_state(&mut table, 0, &1), KeyState::Absent);
+            assert_eq!(index.get_state(&mut table, 1, &1), KeyState::Present(12));
+            assert_eq!(index.get_state(&mut table, 1, &2), KeyState::Absent);
+            assert_eq!(index.get_state(&mut table, 2, &2), KeyState::Present(34));
+            assert_eq!(index.get_state(&mut table, 3, &1), KeyState::Removed);
+            assert_eq!(index.get_state(&mut table, 3, &2), KeyState::Present(34));
+            assert_eq!(index.get_state(&mut table, 4, &2), KeyState::Removed);
+            assert_eq!(index.get_state(&mut table, 5, &1), KeyState::Removed);
+            assert_eq!(index.get_state(&mut table, 5, &2), KeyState::Present(56));
+            assert_eq!(index.get_state(&mut table, 5, &3), KeyState::Removed);
+        }
+
+        // without a clear, an untouched key stays absent
+        let mut index = HashMapIndex::new(|update: HashMapUpdate<u64, u64>| iter::once(update));
+        index.update(&mut table, 2);
+        assert_eq!(index.get_state(&mut table, 3, &3), KeyState::Absent);
+    }
+
+    #[test]
+    fn freeze() {
+        let mut table = VecTable::<(&str, &str)>::new();
+        table.append([("key1", "value1"), ("key2", "value2")]);
+
+        let mut hash_map_index = HashMapIndex::new(tuple_to_insert);
+        hash_map_index.update(&mut table, 2);
+        let frozen = hash_map_index.freeze();
+
+        table.append([("key1", "VALUE1"), ("key3", "value3")]);
+        hash_map_index.update(&mut table, 4);
+
+        let frozen = std::thread::spawn(move || frozen).join().unwrap();
+        assert_eq!(frozen.seq(), 2);
+        assert_eq!(frozen.len(), 2);
+        assert!(!frozen.is_empty());
+        assert_eq!(frozen.get(&"key1"), Some(&"value1"));
+        assert_eq!(frozen.get(&"key3"), None);
+        assert_eq!(
+            frozen
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect::<HashMap<_, _>>(),
+            HashMap::from([("key1", "value1"), ("key2", "value2")])
+        );
+        assert_eq!(hash_map_index.get(&mut table, 4, &"key1"), Some("VALUE1"));
+    }
+
+    #[test]
+    fn feed() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "value1" },
+            HashMapUpdate::Insert { key: "key2", value: "value2" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key3", value: "value3" },
+            HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+            HashMapUpdate::Remove { key: "key3" },
+        ]);
+
+        let mut hash_map_index =
+            HashMapIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+        hash_map_index.update(&mut table, 6);
+        let expected = hash_map_index.get_all(&mut table, 6);
+
+        for from in [0, 2, 4, 6] {
+            let mut mirror = hash_map_index.get_all(&mut table, from);
+            let mut checkpoint = from;
+            for (seq, update) in hash_map_index.feed(&mut table, from) {
+                assert!(seq > checkpoint);
+                checkpoint = seq;
+                match update {
+                    HashMapUpdate::Insert { key, value } => {
+                        mirror.insert(key, value);
+                    }
+                    HashMapUpdate::Remove { key } => {
+                        mirror.remove(&key);
+                    }
+                    HashMapUpdate::Clear => {
+                        mirror.clear();
+                    }
+                }
+            }
+            assert_eq!(mirror, expected, "from {}", from);
+        }
+
+        assert_eq!(
+            hash_map_index.feed(&mut table, 4).collect::<Vec<_>>(),
+            vec![
+                (5, HashMapUpdate::Insert { key: "key1", value: "VALUE1" }),
+                (6, HashMapUpdate::Remove { key: "key3" })
+            ]
+        );
+        assert_eq!(hash_map_index.feed(&mut table, 10).count(), 0);
+    }
+
+    #[test]
+    fn get_all_snapshots() {
+        let mut table = VecTable::<HashMapUpdate<u64, u64>>::new();
+        // a simple linear congruential generator, for repeatable pseudo-random events and reads
+        let mut state = 12345u64;
+        let mut random = move |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        table.append((0..500).map(|_| match random(20) {
+            0 => HashMapUpdate::Clear,
+            1..=5 => HashMapUpdate::Remove { key: random(10) },
+            _ => HashMapUpdate::Insert { key: random(10), value: random(100) },
+        }));
+
+        let mut hash_map_index = HashMapIndex::new(|update: HashMapUpdate<_, _>| vec![update]);
+        let mut snapshot_index =
+            HashMapIndex::with_snapshot_interval(|update: HashMapUpdate<_, _>| vec![update], 16, 8);
+        for seq in [100, 250, 500] {
+            hash_map_index.update(&mut table, seq);
+            snapshot_index.update(&mut table, seq);
+        }
+        assert_eq!(snapshot_index.snapshots.maps.len(), 8);
+        assert_eq!(snapshot_index.snapshots.maps.keys().next_back(), Some(&496));
+
+        for _ in 0..200 {
+            let seq = random(501);
+            assert_eq!(
+                snapshot_index.get_all(&mut table, seq),
+                hash_map_index.get_all(&mut table, seq),
+                "seq {}",
+                seq
+            );
+        }
+    }
+
+    #[test]
+    fn capturing_closure() {
+        let mut table = VecTable::<HashMap<&str, &str>>::new();
+        table.append([
+            HashMap::from([("id", "1"), ("name", "a"), ("color", "red")]),
+            HashMap::from([("id", "2"), ("name", "b"), ("color", "blue")]),
+        ]);
+
+        // the column to index is chosen at runtime
+        let column = String::from("color");
+        let mut hash_map_index = HashMapIndex::new(move |row: HashMap<&str, &str>| {
+            vec![HashMapUpdate::Insert { key: row["id"], value: row[column.as_str()] }]
+        });
+        hash_map_index.update(&mut table, 2);
+
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 2),
+            HashMap::from([("1", "red"), ("2", "blue")])
+        );
+    }
+
+    #[test]
+    fn get_all_clear_multiple_modifications() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+
+        let current_seq = {
+            table.append([
+                HashMapUpdate::Insert { key: "key1", value: "value1" },
+                HashMapUpdate::Clear,
+                HashMapUpdate::Insert { key: "key1", value: "value1" },
+                HashMapUpdate::Remove { key: "key1" },
+                HashMapUpdate::Insert { key: "key1", value: "VALUE1" },
+                HashMapUpdate::Insert { key: "key2", value: "value2" },
+                HashMapUpdate::Clear,
+                HashMapUpdate::Insert { key: "key2", value: "VALUE2" },
+            ]);
+            table.get_current_seq()
+        };
+
+        let mut hash_map_index =
+            HashMapIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+        hash_map_index.update(&mut table, current_seq);
+
+        assert_eq!(current_seq, 8);
+        assert_eq!(hash_map_index.get_current_seq(), 8);
+
+        assert_eq!(hash_map_index.get_all(&mut table, 0), HashMap::new());
+        assert_eq!(hash_map_index.get_all(&mut table, 1), HashMap::from([("key1", "value1")]));
+        assert_eq!(hash_map_index.get_all(&mut table, 2), HashMap::new());
+        assert_eq!(hash_map_index.get_all(&mut table, 3), HashMap::from([("key1", "value1")]));
+        assert_eq!(hash_map_index.get_all(&mut table, 4), HashMap::new());
+        assert_eq!(hash_map_index.get_all(&mut table, 5), HashMap::from([("key1", "VALUE1")]));
+        assert_eq!(
+            hash_map_index.get_all(&mut table, 6),
+            HashMap::from([("key1", "VALUE1"), ("key2", "value2")])
+        );
+        assert_eq!(hash_map_index.get_all(&mut table, 7), HashMap::new());
+        assert_eq!(hash_map_index.get_all(&mut table, 8), HashMap::from([("key2", "VALUE2")]));
+
+        // rewinding without a clear in between stops at the clear before seq
+        let mut hash_map_index =
+            HashMapIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+        hash_map_index.update(&mut table, 5);
+        assert_eq!(hash_map_index.get_all(&mut table, 2), HashMap::new());
+        assert_eq!(hash_map_index.get_all(&mut table, 3), HashMap::from([("key1", "value1")]));
+    }
 }
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::{Index, Seq, SeqType, View};
+
+/// Tracks every key that has ever been touched, e.g. for schema discovery or for enumerating keys to rewind in other
+/// indexes. The set only grows: removing a key or clearing a map doesn't remove it from the history.
+pub struct KeyHistoryIndex<Source, Key, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    current_seq: S,
+    to_keys: Box<dyn Fn(Source::Event) -> Vec<Key> + Send>,
+    keys: HashSet<Key>,
+}
+
+impl<Source, Key, S> Index<S> for KeyHistoryIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            self.keys.extend((self.to_keys)(event));
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, S> KeyHistoryIndex<Source, Key, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+{
+    /// `to_keys` returns every key an event touches, whether it inserts, removes, or overwrites it.
+    pub fn new(to_keys: impl Fn(Source::Event) -> Vec<Key> + Send + 'static) -> Self {
+        Self {
+            current_seq: Default::default(),
+            to_keys: Box::new(to_keys),
+            keys: Default::default(),
+        }
+    }
+
+    /// Returns every key seen up to the current seq.
+    pub fn all_keys_seen(&self) -> &HashSet<Key> {
+        &self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyHistoryIndex;
+    use crate::index::hash_map_index::HashMapUpdate;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table, View};
+    use std::collections::HashSet;
+
+    fn update_to_keys(update: HashMapUpdate<&'static str, &'static str>) -> Vec<&'static str> {
+        match update {
+            HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => vec![key],
+            HashMapUpdate::Clear => vec![],
+        }
+    }
+
+    #[test]
+    fn all_keys_seen() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+
+        let mut key_history_index = KeyHistoryIndex::new(update_to_keys);
+        assert_eq!(key_history_index.all_keys_seen(), &HashSet::new());
+
+        let current_seq = {
+            table.append([
+                HashMapUpdate::Insert { key: "key1", value: "value1" },
+                HashMapUpdate::Insert { key: "key2", value: "value2" },
+                HashMapUpdate::Remove { key: "key1" },
+            ]);
+            table.get_current_seq()
+        };
+        key_history_index.update(&mut table, current_seq);
+        assert_eq!(key_history_index.all_keys_seen(), &HashSet::from(["key1", "key2"]));
+
+        let current_seq = {
+            table.append([
+                HashMapUpdate::Clear,
+                HashMapUpdate::Insert { key: "key3", value: "value3" },
+            ]);
+            table.get_current_seq()
+        };
+        key_history_index.update(&mut table, current_seq);
+        assert_eq!(key_history_index.get_current_seq(), 5);
+        assert_eq!(key_history_index.all_keys_seen(), &HashSet::from(["key1", "key2", "key3"]));
+    }
+}
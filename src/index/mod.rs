@@ -1 +1,45 @@
+pub mod btree_map_index;
+pub mod count_index;
+pub mod diff;
+#[cfg(feature = "digest")]
+pub mod digest_index;
+pub mod group_by_index;
 pub mod hash_map_index;
+pub mod key_history_index;
+pub mod multi_index;
+pub mod multi_map_index;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod pipeline;
+pub mod recent_keys_index;
+pub mod reverse_index;
+pub mod sum_index;
+pub mod unique_index;
+
+/// An index's `to_assignment`, which passes each of an event's updates to a callback rather than returning them, so that
+/// constructors can accept closures returning any `IntoIterator` of updates and `update` needn't collect them. Like the
+/// closures it's built from, it's `Send`, so indexes can be updated on other threads.
+pub(crate) type ToAssignment<Event, Update> = Box<dyn Fn(Event, &mut dyn FnMut(Update)) + Send>;
+
+/// Adapts a `to_assignment` that returns its updates into one that passes them to a callback.
+pub(crate) fn streaming<Event, Update, Updates>(
+    to_assignment: impl Fn(Event) -> Updates + Send + 'static,
+) -> ToAssignment<Event, Update>
+where
+    Updates: IntoIterator<Item = Update> + 'static,
+{
+    Box::new(move |event, emit| {
+        for update in to_assignment(event) {
+            emit(update);
+        }
+    })
+}
+
+/// Collects an event's updates, e.g. to apply them in reverse.
+pub(crate) fn assignments<Event, Update>(
+    to_assignment: &ToAssignment<Event, Update>, event: Event,
+) -> Vec<Update> {
+    let mut result = Vec::new();
+    to_assignment(event, &mut |update| result.push(update));
+    result
+}
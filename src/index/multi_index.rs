@@ -0,0 +1,105 @@
+use crate::{Index, SeqType};
+
+/// Advances a tuple of indexes over the same source together, e.g. several `HashMapIndex`es keyed differently. As an
+/// index, its current seq is the lowest of its indexes', so readers know the seq at which all of them are consistent.
+/// The indexes are queried directly, through `indexes`.
+pub struct MultiIndex<Indexes> {
+    indexes: Indexes,
+}
+
+impl<Indexes> MultiIndex<Indexes> {
+    pub fn new(indexes: Indexes) -> Self {
+        Self { indexes }
+    }
+
+    pub fn indexes(&self) -> &Indexes {
+        &self.indexes
+    }
+
+    pub fn indexes_mut(&mut self) -> &mut Indexes {
+        &mut self.indexes
+    }
+
+    pub fn into_inner(self) -> Indexes {
+        self.indexes
+    }
+}
+
+macro_rules! impl_multi_index {
+    ($first:ident $(, $rest:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<S: SeqType, $first: Index<S>, $($rest: Index<S, Source = $first::Source>),+> Index<S>
+            for MultiIndex<($first, $($rest),+)>
+        {
+            type Source = $first::Source;
+
+            fn update(&mut self, source: &mut Self::Source, seq: S) {
+                let ($first, $($rest),+) = &mut self.indexes;
+                $first.update(source, seq);
+                $($rest.update(source, seq);)+
+            }
+
+            fn get_current_seq(&self) -> S {
+                let ($first, $($rest),+) = &self.indexes;
+                $first.get_current_seq()$(.min($rest.get_current_seq()))+
+            }
+        }
+    };
+}
+
+impl_multi_index!(A, B);
+impl_multi_index!(A, B, C);
+impl_multi_index!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::MultiIndex;
+    use crate::index::count_index::CountIndex;
+    use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    type User = (u64, &'static str);
+
+    #[test]
+    fn update() {
+        let mut table = VecTable::<User>::new();
+        table.append([(1, "alice"), (2, "bob"), (1, "carol")]);
+
+        let by_id =
+            HashMapIndex::new(|(id, name): User| [HashMapUpdate::Insert { key: id, value: name }]);
+        let by_name =
+            HashMapIndex::new(|(id, name): User| [HashMapUpdate::Insert { key: name, value: id }]);
+        let mut multi_index = MultiIndex::new((by_id, by_name));
+        multi_index.update(&mut table, 3);
+        assert_eq!(multi_index.get_current_seq(), 3);
+
+        let (by_id, by_name) = multi_index.indexes();
+        assert_eq!(by_id.get(&mut table, 3, &1), Some("carol"));
+        assert_eq!(by_name.get(&mut table, 3, &"alice"), Some(1));
+        assert_eq!(by_id.get_current_seq(), by_name.get_current_seq());
+    }
+
+    #[test]
+    fn get_current_seq() {
+        let mut table = VecTable::<User>::new();
+        table.append([(1, "alice"), (2, "bob"), (1, "carol")]);
+
+        let mut multi_index = MultiIndex::new((
+            CountIndex::new(|(id, _): User| vec![id]),
+            CountIndex::new(|(_, name): User| vec![name]),
+            CountIndex::new(|_: User| vec![()]),
+        ));
+        multi_index.update(&mut table, 1);
+
+        // the lagging index sets the floor
+        multi_index.indexes_mut().0.update(&mut table, 3);
+        multi_index.indexes_mut().2.update(&mut table, 2);
+        assert_eq!(multi_index.get_current_seq(), 1);
+        assert_eq!(multi_index.lag(&mut table), 2);
+
+        multi_index.update(&mut table, 3);
+        assert_eq!(multi_index.get_current_seq(), 3);
+        assert_eq!(multi_index.into_inner().2.get_count(&mut table, 3, &()), 3);
+    }
+}
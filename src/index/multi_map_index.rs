@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultiMapUpdate<Key, Value>
+where
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    Append { key: Key, value: Value },
+    RemoveKey { key: Key },
+    Clear,
+}
+
+/// Like `HashMapIndex`, but retains every value appended under a key, in order, e.g. the comments on a post.
+pub struct MultiMapIndex<Source, Key, Value, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    current_seq: S,
+    to_assignment: ToAssignment<Source::Event, MultiMapUpdate<Key, Value>>,
+    map: HashMap<Key, Vec<Value>>,
+}
+
+impl<Source, Key, Value, S> Index<S> for MultiMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            for update in assignments(&self.to_assignment, event) {
+                match update {
+                    MultiMapUpdate::Append { key, value } => {
+                        self.map.entry(key).or_default().push(value);
+                    }
+                    MultiMapUpdate::RemoveKey { key } => {
+                        self.map.remove(&key);
+                    }
+                    MultiMapUpdate::Clear => {
+                        self.map.clear();
+                    }
+                }
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, Value, S> MultiMapIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = MultiMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_assignment: streaming(to_assignment),
+            map: Default::default(),
+        }
+    }
+
+    /// Returns the values appended under a key at `seq`, in the order they were appended.
+    pub fn get_values(&self, source: &mut Source, seq: S, key: &Key) -> Vec<Value> {
+        let mut result = self.map.get(key).cloned().unwrap_or_default();
+
+        if seq >= self.current_seq {
+            // read ahead of current sequence: apply un-applied updates to the current values
+            for (_, event) in source.scan(self.current_seq, seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        MultiMapUpdate::Append { key: k, value } if &k == key => {
+                            result.push(value);
+                        }
+                        MultiMapUpdate::RemoveKey { key: k } if &k == key => {
+                            result.clear();
+                        }
+                        MultiMapUpdate::Clear => {
+                            result.clear();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            result
+        } else {
+            // read behind current sequence: count the values appended since seq
+            let mut appended = 0;
+            let mut reset = false;
+            for (_, event) in source.scan(seq, self.current_seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        MultiMapUpdate::Append { key: k, .. } if &k == key => {
+                            appended += 1;
+                        }
+                        MultiMapUpdate::RemoveKey { key: k } if &k == key => {
+                            reset = true;
+                        }
+                        MultiMapUpdate::Clear => {
+                            reset = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if !reset {
+                // the current values extend the values at seq, so drop the ones appended since
+                result.truncate(result.len() - appended);
+                return result;
+            }
+
+            // otherwise, replay from the most recent removal of the key at or before seq
+            let mut result = Vec::new();
+            'scan: for (_, event) in source.scan_rev(S::MIN, seq) {
+                for update in assignments(&self.to_assignment, event).into_iter().rev() {
+                    match update {
+                        MultiMapUpdate::Append { key: k, value } if &k == key => {
+                            result.push(value);
+                        }
+                        MultiMapUpdate::RemoveKey { key: k } if &k == key => {
+                            break 'scan;
+                        }
+                        MultiMapUpdate::Clear => {
+                            break 'scan;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            result.reverse();
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultiMapIndex, MultiMapUpdate};
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    #[test]
+    fn get_values() {
+        let mut table = VecTable::<MultiMapUpdate<&str, i32>>::new();
+        table.append([
+            MultiMapUpdate::Append { key: "post1", value: 1 },
+            MultiMapUpdate::Append { key: "post2", value: 2 },
+            MultiMapUpdate::Append { key: "post1", value: 3 },
+            MultiMapUpdate::Append { key: "post2", value: 4 },
+            MultiMapUpdate::RemoveKey { key: "post1" },
+            MultiMapUpdate::Append { key: "post1", value: 5 },
+            MultiMapUpdate::Append { key: "post2", value: 6 },
+            MultiMapUpdate::Clear,
+            MultiMapUpdate::Append { key: "post2", value: 7 },
+        ]);
+
+        let expected: [(Vec<i32>, Vec<i32>); 10] = [
+            (vec![], vec![]),
+            (vec![1], vec![]),
+            (vec![1], vec![2]),
+            (vec![1, 3], vec![2]),
+            (vec![1, 3], vec![2, 4]),
+            (vec![], vec![2, 4]),
+            (vec![5], vec![2, 4]),
+            (vec![5], vec![2, 4, 6]),
+            (vec![], vec![]),
+            (vec![], vec![7]),
+        ];
+
+        for current_seq in 0..=9 {
+            let mut multi_map_index =
+                MultiMapIndex::new(|assignment: MultiMapUpdate<_, _>| vec![assignment]);
+            multi_map_index.update(&mut table, current_seq);
+            assert_eq!(multi_map_index.get_current_seq(), current_seq);
+
+            for (seq, (post1, post2)) in expected.iter().enumerate() {
+                let seq = seq as u64;
+                assert_eq!(&multi_map_index.get_values(&mut table, seq, &"post1"), post1);
+                assert_eq!(&multi_map_index.get_values(&mut table, seq, &"post2"), post2);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use rayon::prelude::*;
+
+use crate::{Index, SeqType};
+
+/// Updates each index to `seq` on rayon's thread pool, e.g. when many independent indexes read the same source. Scans
+/// need a mutable source, so each index scans its own clone; this suits sources whose clones share storage, like
+/// `VecTable`.
+pub fn update_all_parallel<I, S>(indexes: &mut [I], source: &I::Source, seq: S)
+where
+    I: Index<S> + Send,
+    S: SeqType + Sync,
+    I::Source: Clone + Sync,
+{
+    indexes
+        .par_iter_mut()
+        .for_each(|index| index.update(&mut source.clone(), seq));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::update_all_parallel;
+    use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+    use std::collections::HashMap;
+
+    #[test]
+    fn update_all() {
+        let mut table = VecTable::<(u64, u64)>::new();
+        table.append((0..1000).map(|i| (i % 10, i)));
+
+        // each index keeps the keys with a different remainder
+        let mut indexes = (0..4)
+            .map(|remainder| {
+                HashMapIndex::new(move |(key, value): (u64, u64)| {
+                    (key % 4 == remainder).then_some(HashMapUpdate::Insert { key, value })
+                })
+            })
+            .collect::<Vec<_>>();
+        update_all_parallel(&mut indexes, &table, 1000);
+
+        for (remainder, index) in indexes.iter().enumerate() {
+            assert_eq!(index.get_current_seq(), 1000);
+            let expected = (990..1000)
+                .filter(|value| value % 10 % 4 == remainder as u64)
+                .map(|value| (value % 10, value))
+                .collect::<HashMap<_, _>>();
+            assert_eq!(index.get_all(&mut table.clone(), 1000), expected);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+use crate::View;
+
+type Transform<In, Out> = Box<dyn Fn(In) -> Option<Out> + Send>;
+
+/// Declarative "filter then map then index" over a source's events. Each stage is composed into a single transform
+/// which becomes the `to_assignment` of the resulting index.
+pub struct IndexPipeline<Source: View, Event> {
+    transform: Transform<Source::Event, Event>,
+    source: PhantomData<Source>,
+}
+
+impl<Source: View> IndexPipeline<Source, Source::Event>
+where
+    Source::Event: 'static,
+{
+    pub fn new() -> Self {
+        Self { transform: Box::new(Some), source: PhantomData }
+    }
+}
+
+impl<Source: View> Default for IndexPipeline<Source, Source::Event>
+where
+    Source::Event: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The source is only used to infer the event type; like the indexes it builds, the pipeline doesn't hold its source.
+impl<Source: View> From<&Source> for IndexPipeline<Source, Source::Event>
+where
+    Source::Event: 'static,
+{
+    fn from(_: &Source) -> Self {
+        Self::new()
+    }
+}
+
+impl<Source: View + 'static, Event: 'static> IndexPipeline<Source, Event> {
+    /// Drops events for which `predicate` returns false.
+    pub fn filter(self, predicate: impl Fn(&Event) -> bool + Send + 'static) -> Self {
+        let transform = self.transform;
+        Self {
+            transform: Box::new(move |event| transform(event).filter(|event| predicate(event))),
+            source: PhantomData,
+        }
+    }
+
+    /// Transforms each event that survived the preceding stages.
+    pub fn map<Mapped: 'static>(
+        self, f: impl Fn(Event) -> Mapped + Send + 'static,
+    ) -> IndexPipeline<Source, Mapped> {
+        let transform = self.transform;
+        IndexPipeline {
+            transform: Box::new(move |event| transform(event).map(&f)),
+            source: PhantomData,
+        }
+    }
+
+    /// Builds a `HashMapIndex` which inserts `value_fn(event)` at `key_fn(&event)` for each event that survived the
+    /// preceding stages.
+    pub fn into_hash_map<Key, Value>(
+        self, key_fn: impl Fn(&Event) -> Key + Send + 'static,
+        value_fn: impl Fn(Event) -> Value + Send + 'static,
+    ) -> HashMapIndex<Source, Key, Value>
+    where
+        Key: Clone + Eq + Hash + 'static,
+        Value: Clone + 'static,
+    {
+        let transform = self.transform;
+        HashMapIndex::new(move |event| {
+            transform(event)
+                .map(|event| HashMapUpdate::Insert { key: key_fn(&event), value: value_fn(event) })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexPipeline;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table, View};
+    use std::collections::HashMap;
+
+    #[test]
+    fn filter_map_into_hash_map() {
+        let mut table = VecTable::<i32>::new();
+
+        let current_seq = {
+            table.append([1, 2, 3, 4, 5, 6, 12]);
+            table.get_current_seq()
+        };
+
+        let mut index = IndexPipeline::from(&table)
+            .filter(|event| event % 2 == 0)
+            .map(|event| event * 10)
+            .into_hash_map(|event| event % 30, |event| event);
+        index.update(&mut table, current_seq);
+
+        assert_eq!(index.get_all(&mut table, 0), HashMap::new());
+        assert_eq!(index.get_all(&mut table, 2), HashMap::from_iter(vec![(20, 20)].into_iter()));
+        assert_eq!(
+            index.get_all(&mut table, 6),
+            HashMap::from_iter(vec![(20, 20), (10, 40), (0, 60)].into_iter())
+        );
+        assert_eq!(
+            index.get_all(&mut table, 7),
+            HashMap::from_iter(vec![(20, 20), (10, 40), (0, 120)].into_iter())
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::index::hash_map_index::HashMapUpdate;
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Seq, SeqType, View};
+
+/// Finds the most recently modified keys of a `HashMapIndex`-style map by scanning the source newest-first and stopping
+/// as soon as enough keys are found. It keeps no state of its own, so it reads at any seq without updating.
+pub struct RecentKeysIndex<Source, Key, Value, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    to_assignment: ToAssignment<Source::Event, HashMapUpdate<Key, Value>>,
+}
+
+impl<Source, Key, Value, S> RecentKeysIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self { to_assignment: streaming(to_assignment) }
+    }
+
+    /// Returns up to `n` keys present in the map at `seq` with their values, most recently modified first. A key whose
+    /// most recent modification removed it is skipped, and nothing before the most recent clear is considered.
+    pub fn recent(&self, source: &mut Source, seq: S, n: usize) -> Vec<(Key, Value)> {
+        let mut result = Vec::new();
+        let mut seen_keys = HashSet::new();
+        if n == 0 {
+            return result;
+        }
+        for (_, event) in source.scan_rev(S::MIN, seq) {
+            for update in assignments(&self.to_assignment, event).into_iter().rev() {
+                match update {
+                    HashMapUpdate::Clear => {
+                        return result;
+                    }
+                    HashMapUpdate::Insert { key, value } => {
+                        // only the most recent modification to each key counts
+                        if seen_keys.insert(key.clone()) {
+                            result.push((key, value));
+                            if result.len() == n {
+                                return result;
+                            }
+                        }
+                    }
+                    HashMapUpdate::Remove { key } => {
+                        seen_keys.insert(key);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecentKeysIndex;
+    use crate::index::hash_map_index::HashMapUpdate;
+    use crate::testing::fixtures::CountingView;
+    use crate::Table;
+
+    type Update = HashMapUpdate<&'static str, i32>;
+
+    #[test]
+    fn recent() {
+        let mut view = CountingView::new(Default::default());
+        view.table.append([
+            HashMapUpdate::Insert { key: "key1", value: 1 },
+            HashMapUpdate::Insert { key: "key2", value: 2 },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key3", value: 3 },
+            HashMapUpdate::Insert { key: "key4", value: 4 },
+            HashMapUpdate::Insert { key: "key5", value: 5 },
+            HashMapUpdate::Insert { key: "key3", value: 33 },
+            HashMapUpdate::Remove { key: "key5" },
+            HashMapUpdate::Insert { key: "key6", value: 6 },
+        ]);
+
+        let index = RecentKeysIndex::new(|update: Update| vec![update]);
+
+        assert_eq!(index.recent(&mut view, 9, 2), vec![("key6", 6), ("key3", 33)]);
+        // stopped after finding key3, without scanning further back
+        assert_eq!(view.counts.events.get(), 3);
+
+        view.counts.reset();
+        assert_eq!(index.recent(&mut view, 9, 10), vec![("key6", 6), ("key3", 33), ("key4", 4)]);
+        // stopped at the clear
+        assert_eq!(view.counts.events.get(), 7);
+
+        assert_eq!(index.recent(&mut view, 6, 2), vec![("key5", 5), ("key4", 4)]);
+        assert_eq!(index.recent(&mut view, 2, 10), vec![("key2", 2), ("key1", 1)]);
+        assert_eq!(index.recent(&mut view, 9, 0), vec![]);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::index::hash_map_index::HashMapUpdate;
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+/// Maps each value of a `HashMapIndex`-style map back to the keys holding it, e.g. to answer "which keys currently hold
+/// this value". Consumes the same updates as `HashMapIndex`.
+pub struct ReverseIndex<Source, Key, Value, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + Eq + Hash,
+{
+    current_seq: S,
+    to_assignment: ToAssignment<Source::Event, HashMapUpdate<Key, Value>>,
+    // the forward map is needed to find the value a key is moving away from
+    forward: HashMap<Key, Value>,
+    reverse: HashMap<Value, HashSet<Key>>,
+}
+
+impl<Source, Key, Value, S> Index<S> for ReverseIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + Eq + Hash,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            for update in assignments(&self.to_assignment, event) {
+                match update {
+                    HashMapUpdate::Insert { key, value } => {
+                        self.remove(&key);
+                        self.reverse
+                            .entry(value.clone())
+                            .or_default()
+                            .insert(key.clone());
+                        self.forward.insert(key, value);
+                    }
+                    HashMapUpdate::Remove { key } => {
+                        self.remove(&key);
+                    }
+                    HashMapUpdate::Clear => {
+                        self.forward.clear();
+                        self.reverse.clear();
+                    }
+                }
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, Value, S> ReverseIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + Eq + Hash,
+{
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_assignment: streaming(to_assignment),
+            forward: Default::default(),
+            reverse: Default::default(),
+        }
+    }
+
+    /// Removes a key from the set for its current value, dropping the set if it empties.
+    fn remove(&mut self, key: &Key) {
+        if let Some(value) = self.forward.remove(key) {
+            if let Some(keys) = self.reverse.get_mut(&value) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.reverse.remove(&value);
+                }
+            }
+        }
+    }
+
+    /// Returns the keys holding `value` at `seq`.
+    pub fn get_keys(&self, source: &mut Source, seq: S, value: &Value) -> HashSet<Key> {
+        let current = self.reverse.get(value).cloned().unwrap_or_default();
+
+        if seq >= self.current_seq {
+            // read ahead of current sequence: overlay un-applied updates on the current state
+            let mut overlay = HashMap::new();
+            let mut cleared = false;
+            for (_, event) in source.scan(self.current_seq, seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        HashMapUpdate::Insert { key, value } => {
+                            overlay.insert(key, Some(value));
+                        }
+                        HashMapUpdate::Remove { key } => {
+                            overlay.insert(key, None);
+                        }
+                        HashMapUpdate::Clear => {
+                            overlay.clear();
+                            cleared = true;
+                        }
+                    }
+                }
+            }
+            Self::overlay(if cleared { HashSet::new() } else { current }, overlay, value)
+        } else {
+            // read behind current sequence: determine which keys have changed since the state we're reading at
+            let mut modified_keys = HashSet::new();
+            let mut cleared = false;
+            for (_, event) in source.scan(seq, self.current_seq) {
+                for update in assignments(&self.to_assignment, event) {
+                    match update {
+                        HashMapUpdate::Insert { key, .. } | HashMapUpdate::Remove { key } => {
+                            modified_keys.insert(key);
+                        }
+                        HashMapUpdate::Clear => {
+                            cleared = true;
+                        }
+                    }
+                }
+            }
+
+            // look back from seq for the most recent modification to each modified key (to every key, if cleared),
+            // stopping at the most recent clear
+            let mut overlay = HashMap::new();
+            'scan: for (_, event) in source.scan(S::MIN, seq).rev() {
+                for update in assignments(&self.to_assignment, event).into_iter().rev() {
+                    let (key, value) = match update {
+                        HashMapUpdate::Clear => break 'scan,
+                        HashMapUpdate::Insert { key, value } => (key, Some(value)),
+                        HashMapUpdate::Remove { key } => (key, None),
+                    };
+                    if (cleared || modified_keys.contains(&key)) && !overlay.contains_key(&key) {
+                        overlay.insert(key, value);
+                    }
+                }
+
+                // once we find all modified keys, we're done
+                if !cleared && overlay.len() == modified_keys.len() {
+                    break;
+                }
+            }
+
+            // remaining keys were absent at seq
+            for key in modified_keys {
+                overlay.entry(key).or_insert(None);
+            }
+            Self::overlay(if cleared { HashSet::new() } else { current }, overlay, value)
+        }
+    }
+
+    /// Applies each key's value in `overlay` (`None` if absent) to the set of keys holding `value`.
+    fn overlay(
+        mut keys: HashSet<Key>, overlay: HashMap<Key, Option<Value>>, value: &Value,
+    ) -> HashSet<Key> {
+        for (key, overlay_value) in overlay {
+            if overlay_value.as_ref() == Some(value) {
+                keys.insert(key);
+            } else {
+                keys.remove(&key);
+            }
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReverseIndex;
+    use crate::index::hash_map_index::HashMapUpdate;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+    use std::collections::HashSet;
+
+    #[test]
+    fn get_keys() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "key1", value: "red" },
+            HashMapUpdate::Insert { key: "key2", value: "red" },
+            HashMapUpdate::Insert { key: "key3", value: "blue" },
+            HashMapUpdate::Insert { key: "key1", value: "blue" },
+            HashMapUpdate::Remove { key: "key3" },
+            HashMapUpdate::Clear,
+            HashMapUpdate::Insert { key: "key2", value: "blue" },
+        ]);
+
+        let expected = [
+            (HashSet::new(), HashSet::new()),
+            (HashSet::from(["key1"]), HashSet::new()),
+            (HashSet::from(["key1", "key2"]), HashSet::new()),
+            (HashSet::from(["key1", "key2"]), HashSet::from(["key3"])),
+            // key1 changes value: red shrinks and blue grows
+            (HashSet::from(["key2"]), HashSet::from(["key1", "key3"])),
+            (HashSet::from(["key2"]), HashSet::from(["key1"])),
+            (HashSet::new(), HashSet::new()),
+            (HashSet::new(), HashSet::from(["key2"])),
+        ];
+
+        for current_seq in 0..=7 {
+            let mut reverse_index =
+                ReverseIndex::new(|assignment: HashMapUpdate<_, _>| vec![assignment]);
+            reverse_index.update(&mut table, current_seq);
+            assert_eq!(reverse_index.get_current_seq(), current_seq);
+
+            for (seq, (red, blue)) in expected.iter().enumerate() {
+                let seq = seq as u64;
+                assert_eq!(&reverse_index.get_keys(&mut table, seq, &"red"), red, "seq {}", seq);
+                assert_eq!(&reverse_index.get_keys(&mut table, seq, &"blue"), blue, "seq {}", seq);
+            }
+        }
+    }
+}
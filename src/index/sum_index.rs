@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Sub};
+
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+/// Sums numeric deltas per key. Deltas can be subtracted back out, so reading behind the current seq rewinds the sum by
+/// the deltas in between rather than rescanning history.
+pub struct SumIndex<Source, Key, N, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    N: Clone + Default + Add<Output = N> + Sub<Output = N>,
+{
+    current_seq: S,
+    to_deltas: ToAssignment<Source::Event, (Key, N)>,
+    sums: HashMap<Key, N>,
+}
+
+impl<Source, Key, N, S> Index<S> for SumIndex<Source, Key, N, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    N: Clone + Default + Add<Output = N> + Sub<Output = N>,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (_, event) in source.scan(self.current_seq, seq) {
+            for (key, delta) in assignments(&self.to_deltas, event) {
+                let sum = self.sums.entry(key).or_default();
+                *sum = sum.clone() + delta;
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, N, S> SumIndex<Source, Key, N, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    N: Clone + Default + Add<Output = N> + Sub<Output = N>,
+{
+    /// `to_deltas` returns the amount each event adds to the sum for each key.
+    pub fn new<Deltas>(to_deltas: impl Fn(Source::Event) -> Deltas + Send + 'static) -> Self
+    where
+        Deltas: IntoIterator<Item = (Key, N)> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_deltas: streaming(to_deltas),
+            sums: Default::default(),
+        }
+    }
+
+    /// Returns the sum for a single key at `seq`.
+    pub fn get_sum(&self, source: &mut Source, seq: S, key: &Key) -> N {
+        let mut result = self.sums.get(key).cloned().unwrap_or_default();
+        if seq >= self.current_seq {
+            // read ahead of current sequence: add deltas since current seq
+            for (_, event) in source.scan(self.current_seq, seq) {
+                for (k, delta) in assignments(&self.to_deltas, event) {
+                    if &k == key {
+                        result = result + delta;
+                    }
+                }
+            }
+        } else {
+            // read behind current sequence: subtract deltas since seq
+            for (_, event) in source.scan(seq, self.current_seq) {
+                for (k, delta) in assignments(&self.to_deltas, event) {
+                    if &k == key {
+                        result = result - delta;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SumIndex;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    #[test]
+    fn get_sum() {
+        let mut table = VecTable::<(&str, i64)>::new();
+        table.append([("a", 10), ("b", 5), ("a", -3), ("a", -20), ("b", 1)]);
+
+        let mut sum_index = SumIndex::new(|delta: (&str, i64)| [delta]);
+        sum_index.update(&mut table, 3);
+        assert_eq!(sum_index.get_current_seq(), 3);
+
+        // behind
+        assert_eq!(sum_index.get_sum(&mut table, 0, &"a"), 0);
+        assert_eq!(sum_index.get_sum(&mut table, 1, &"a"), 10);
+        assert_eq!(sum_index.get_sum(&mut table, 1, &"b"), 0);
+        assert_eq!(sum_index.get_sum(&mut table, 2, &"b"), 5);
+
+        // at
+        assert_eq!(sum_index.get_sum(&mut table, 3, &"a"), 7);
+        assert_eq!(sum_index.get_sum(&mut table, 3, &"c"), 0);
+
+        // ahead
+        assert_eq!(sum_index.get_sum(&mut table, 4, &"a"), -13);
+        assert_eq!(sum_index.get_sum(&mut table, 5, &"b"), 6);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::index::hash_map_index::HashMapUpdate;
+use crate::index::{assignments, streaming, ToAssignment};
+use crate::{Index, Seq, SeqType, View};
+
+/// Like `HashMapIndex`, maintains a last-write-wins map, but records each insert that changes the value of a key that's
+/// already present, e.g. to detect integrity problems in a field that's meant to be unique while replaying a log.
+/// Removing a key (or clearing the map) before reinserting it is not a violation.
+pub struct UniqueIndex<Source, Key, Value, S = Seq>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + PartialEq,
+{
+    current_seq: S,
+    to_assignment: ToAssignment<Source::Event, HashMapUpdate<Key, Value>>,
+    map: HashMap<Key, Value>,
+    violations: Vec<(S, Key)>,
+}
+
+impl<Source, Key, Value, S> Index<S> for UniqueIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + PartialEq,
+{
+    type Source = Source;
+
+    fn update(&mut self, source: &mut Self::Source, seq: S) {
+        if seq < self.current_seq {
+            return;
+        }
+        for (event_seq, event) in source.scan(self.current_seq, seq) {
+            for update in assignments(&self.to_assignment, event) {
+                match update {
+                    HashMapUpdate::Insert { key, value } => {
+                        if self
+                            .map
+                            .get(&key)
+                            .is_some_and(|existing| existing != &value)
+                        {
+                            self.violations.push((event_seq, key.clone()));
+                        }
+                        self.map.insert(key, value);
+                    }
+                    HashMapUpdate::Remove { key } => {
+                        self.map.remove(&key);
+                    }
+                    HashMapUpdate::Clear => {
+                        self.map.clear();
+                    }
+                }
+            }
+        }
+
+        self.current_seq = seq;
+    }
+
+    fn get_current_seq(&self) -> S {
+        self.current_seq
+    }
+}
+
+impl<Source, Key, Value, S> UniqueIndex<Source, Key, Value, S>
+where
+    Source: View<S>,
+    S: SeqType,
+    Key: Clone + Eq + Hash,
+    Value: Clone + PartialEq,
+{
+    pub fn new<Updates>(to_assignment: impl Fn(Source::Event) -> Updates + Send + 'static) -> Self
+    where
+        Updates: IntoIterator<Item = HashMapUpdate<Key, Value>> + 'static,
+    {
+        Self {
+            current_seq: Default::default(),
+            to_assignment: streaming(to_assignment),
+            map: Default::default(),
+            violations: Default::default(),
+        }
+    }
+
+    /// Returns the value associated with a key as of the current seq.
+    pub fn get(&self, key: &Key) -> Option<&Value> {
+        self.map.get(key)
+    }
+
+    /// Returns the seq and key of each insert so far that changed the value of a present key, in seq order.
+    pub fn violations(&self) -> &[(S, Key)] {
+        &self.violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UniqueIndex;
+    use crate::index::hash_map_index::HashMapUpdate;
+    use crate::table::vec::VecTable;
+    use crate::{Index, Table};
+
+    #[test]
+    fn violations() {
+        let mut table = VecTable::<HashMapUpdate<&str, &str>>::new();
+        table.append([
+            HashMapUpdate::Insert { key: "alice@example.com", value: "user1" },
+            HashMapUpdate::Insert { key: "bob@example.com", value: "user2" },
+            // rewriting the same value is fine
+            HashMapUpdate::Insert { key: "alice@example.com", value: "user1" },
+            HashMapUpdate::Insert { key: "bob@example.com", value: "user3" },
+            // so is reusing a removed key
+            HashMapUpdate::Remove { key: "alice@example.com" },
+            HashMapUpdate::Insert { key: "alice@example.com", value: "user4" },
+        ]);
+
+        let mut unique_index = UniqueIndex::new(|update: HashMapUpdate<_, _>| Some(update));
+        unique_index.update(&mut table, 3);
+        assert!(unique_index.violations().is_empty());
+
+        unique_index.update(&mut table, 6);
+        assert_eq!(unique_index.violations(), &[(4, "bob@example.com")]);
+
+        // the conflicting write still wins
+        assert_eq!(unique_index.get(&"bob@example.com"), Some(&"user3"));
+        assert_eq!(unique_index.get(&"alice@example.com"), Some(&"user4"));
+    }
+}
@@ -1,37 +1,169 @@
+#[cfg(feature = "async")]
+pub mod async_view;
+pub mod error;
 pub mod index;
+pub mod scheduler;
 pub mod table;
+pub mod testing;
 pub mod view;
 
-use std::iter::DoubleEndedIterator;
+use std::fmt::Debug;
+use std::iter::{DoubleEndedIterator, Rev};
 
+/// The default sequence number type.
 pub type Seq = u64;
 
-pub trait View {
+/// A type of sequence number. Views, tables, and indexes are generic over it, defaulting to `Seq`, so e.g. a log that
+/// needs wider logical clocks can use `u128`.
+pub trait SeqType: Copy + Debug + Default + Ord {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// Returns the following sequence number, or `None` if it would overflow.
+    fn checked_next(self) -> Option<Self>;
+
+    /// Returns the preceding sequence number, or `None` if it would underflow.
+    fn checked_prev(self) -> Option<Self>;
+
+    /// Returns `self - other`, or the minimum sequence number if that would underflow.
+    fn saturating_sub(self, other: Self) -> Self;
+}
+
+macro_rules! impl_seq_type {
+    ($($t:ty),*) => {
+        $(
+            impl SeqType for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$t>::saturating_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_seq_type!(u32, u64, u128, usize);
+
+pub trait View<S: SeqType = Seq> {
     type Event;
-    type Iterator: DoubleEndedIterator<Item = (Seq, Self::Event)>;
+    type Iterator: DoubleEndedIterator<Item = (S, Self::Event)>;
+
+    /// Scan the view for events between the given sequences, from the lowest sequence number to the highest. Returns an
+    /// double-ended iterator over the events. No work is done until the iterator is consumed. An `end_inclusive` beyond
+    /// the last event is clamped to it. The range is empty if `start_exclusive` is at least `end_inclusive`; use
+    /// `scan_rev` to scan from the highest sequence number to the lowest.
+    fn scan(&mut self, start_exclusive: S, end_inclusive: S) -> Self::Iterator;
 
-    /// Scan the view for events between the given sequences. Returns an double-ended iterator over the events. No work
-    /// is done until the iterator is consumed.
-    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator;
+    /// Like `scan` over the same range, but from the highest sequence number to the lowest.
+    fn scan_rev(&mut self, start_exclusive: S, end_inclusive: S) -> Rev<Self::Iterator> {
+        self.scan(start_exclusive, end_inclusive).rev()
+    }
 
-    /// Returns the current sequence number of the view. All new events will have a sequence number greater than this.
-    fn get_current_seq(&mut self) -> Seq;
+    /// Returns the current sequence number of the view: the highest sequence number assigned so far, not one past it.
+    /// All new events will have a sequence number greater than this. An empty view has a current sequence number of 0.
+    fn get_current_seq(&mut self) -> S;
+
+    /// Hints that the events between the given sequences will be scanned soon, e.g. so a file-backed view can pre-warm
+    /// the page cache. This has no semantic effect; the default does nothing, which suits in-memory views.
+    fn prefetch(&mut self, _start_exclusive: S, _end_inclusive: S) {}
+
+    /// Returns the sequence number of the earliest event in the view, or `None` if the view is empty.
+    fn first_seq(&mut self) -> Option<S> {
+        self.scan(S::MIN, S::MAX).next().map(|(seq, _)| seq)
+    }
+
+    /// Returns a sequence number at or above that of every event in the view, e.g. so a `CompositeView` can skip a node
+    /// with nothing in a scan's range without scanning it. The default, the maximum sequence number, is always sound;
+    /// tables return their current sequence numbers, as their events are all at or below them.
+    fn seq_upper_bound(&mut self) -> S {
+        S::MAX
+    }
+
+    /// Returns the event at `seq`, or `None` if no event has that sequence number, e.g. because it hasn't been assigned
+    /// yet or because the view's sequence numbers are sparse, like those of a `CompositeView`'s nodes.
+    fn get_event(&mut self, seq: S) -> Option<(S, Self::Event)> {
+        // the minimum sequence number is never scanned, since scans exclude their start
+        let start_exclusive = seq.checked_prev()?;
+        self.scan(start_exclusive, seq).next()
+    }
 }
 
-pub trait Table: View {
-    /// Write the given events to the table. Returns the sequence numbers assigned, in order.
-    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq>;
+/// A view whose events are all at or below its current sequence number: events beyond it don't exist yet, so scanning to
+/// an `end_inclusive` greater than `get_current_seq()` is equivalent to scanning to `get_current_seq()`.
+pub trait Table<S: SeqType = Seq>: View<S> {
+    /// Write the given events to the table. Returns the sequence numbers assigned, in order. Writing no events is a
+    /// no-op: it returns an empty `Vec` and does not advance the current sequence number.
+    ///
+    /// Sequence numbers are assigned in iteration order, so callers must pass an ordered iterable; collections with
+    /// unspecified iteration order assign unspecified sequence numbers:
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use parasol_db::table::vec::VecTable;
+    /// use parasol_db::Table;
+    ///
+    /// let mut table = VecTable::new();
+    ///
+    /// // don't do this: either event may be assigned the lower sequence number
+    /// table.append(HashSet::from([12, 34]));
+    ///
+    /// // do this instead
+    /// table.append_ordered(vec![56, 78]);
+    /// ```
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<S>;
+
+    /// Write the given events to the table in order. Equivalent to `append`, but makes the ordering explicit.
+    fn append_ordered(&mut self, events: Vec<Self::Event>) -> Vec<S> {
+        self.append(events)
+    }
 
     /// Sets the current sequence number of the table unless its sequence number is already greater.
-    fn set_current_seq(&mut self, seq: Seq);
+    fn set_current_seq(&mut self, seq: S);
 }
 
-pub trait Index {
-    type Source: View;
+/// A table whose writes can fail, e.g. because it's backed by a disk that can fill up.
+pub trait TryTable<S: SeqType = Seq>: View<S> {
+    type Error;
 
-    /// Incorporates all changes up to and including the given sequence number into the index.
-    fn update(&mut self, source: &mut Self::Source, seq: Seq);
+    /// Write the given events to the table. Returns the sequence numbers assigned, in order, or an error if none were
+    /// written. Implementations must be all-or-nothing: a failed write leaves no events behind, so callers like
+    /// `RetryTable` can write the same events again without duplicating any.
+    fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+        &mut self, events: Iter,
+    ) -> Result<Vec<S>, Self::Error>;
+}
+
+pub trait Index<S: SeqType = Seq> {
+    type Source: View<S>;
+
+    /// Incorporates all changes up to and including the given sequence number into the index. Indexes can't rewind, so
+    /// updating to a sequence number behind the current one leaves the index as it is.
+    fn update(&mut self, source: &mut Self::Source, seq: S);
 
     /// Returns the sequence number for which all changes up to and including it have been incorporated into the index.
-    fn get_current_seq(&self) -> Seq;
+    fn get_current_seq(&self) -> S;
+
+    /// Returns how far the index is behind the source, in sequence numbers.
+    fn lag(&self, source: &mut Self::Source) -> S {
+        source
+            .get_current_seq()
+            .saturating_sub(self.get_current_seq())
+    }
+}
+
+/// A fire-and-forget side effect run for each newly appended event, e.g. sending an email or calling a webhook. Unlike
+/// an index, a handler holds no queryable state. A `BatchScheduler` runs its handlers.
+pub trait EventHandler<Event, S: SeqType = Seq> {
+    fn handle(&mut self, seq: S, event: &Event);
 }
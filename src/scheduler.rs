@@ -0,0 +1,344 @@
+use std::time::{Duration, Instant};
+
+use crate::{EventHandler, Index, Seq, SeqType, Table};
+
+/// Appends to a table immediately but defers updating an index until `threshold` appended events are pending, or until
+/// `flush`, so ingesting in small batches doesn't update the index after each one. Several indexes over the table can
+/// be scheduled together with a `MultiIndex`.
+///
+/// The scheduler's current seq is the table's, so it includes pending events, but the index lags until it's flushed:
+/// reads through it at the index's current seq see the flushed state, and reads past it pay to replay pending events.
+///
+/// Each flush then runs the scheduler's handlers on the events it flushed, in seq order, so handlers can read the index
+/// consistently with the event they're handling.
+pub struct BatchScheduler<T: Table<S>, I: Index<S, Source = T>, S: SeqType = Seq> {
+    table: T,
+    index: I,
+    handlers: Vec<Box<dyn EventHandler<T::Event, S>>>,
+    threshold: usize,
+    pending: usize,
+    // the table's current seq as of the last flush, after which events are yet to be handled
+    flushed_seq: S,
+}
+
+impl<T: Table<S>, I: Index<S, Source = T>, S: SeqType> BatchScheduler<T, I, S> {
+    /// Panics if `threshold` is 0.
+    pub fn new(table: T, index: I, threshold: usize) -> Self {
+        assert!(threshold > 0, "threshold must be positive");
+        let flushed_seq = index.get_current_seq();
+        Self { table, index, handlers: Vec::new(), threshold, pending: 0, flushed_seq }
+    }
+
+    /// Adds a handler, run on each event flushed from now on.
+    pub fn add_handler(&mut self, handler: impl EventHandler<T::Event, S> + 'static) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Appends the events to the table, then updates the index if that brings the number of pending events to the
+    /// threshold. An empty batch does neither.
+    pub fn write<Iter: IntoIterator<Item = T::Event>>(&mut self, events: Iter) -> Vec<S> {
+        let result = self.table.append(events);
+        if result.is_empty() {
+            return result;
+        }
+        self.pending += result.len();
+        if self.pending >= self.threshold {
+            self.flush();
+        }
+        result
+    }
+
+    /// Updates the index to the table's current seq, then runs the handlers on the events since the last flush.
+    pub fn flush(&mut self) {
+        let seq = self.table.get_current_seq();
+        self.index.update(&mut self.table, seq);
+        self.pending = 0;
+
+        if !self.handlers.is_empty() {
+            for (event_seq, event) in self.table.scan(self.flushed_seq, seq) {
+                for handler in &mut self.handlers {
+                    handler.handle(event_seq, &event);
+                }
+            }
+        }
+        self.flushed_seq = seq;
+    }
+
+    /// Returns the number of events appended since the index was last updated.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    pub fn get_current_seq(&mut self) -> S {
+        self.table.get_current_seq()
+    }
+
+    /// Returns the table and the index, e.g. to read the index with the table as its source.
+    pub fn parts_mut(&mut self) -> (&mut T, &I) {
+        (&mut self.table, &self.index)
+    }
+
+    pub fn into_inner(self) -> (T, I) {
+        (self.table, self.index)
+    }
+}
+
+/// A call to a `BatchScheduler`, as recorded by a `RecordingScheduler`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScheduledCall<Event> {
+    Write(Vec<Event>),
+    Flush,
+}
+
+/// A recorded call along with when it was made, as the recording scheduler's clock read at the time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedCall<Event> {
+    pub at: Duration,
+    pub call: ScheduledCall<Event>,
+}
+
+/// The calls recorded by a `RecordingScheduler`, in the order they were made.
+pub type Schedule<Event> = Vec<RecordedCall<Event>>;
+
+/// Wraps a `BatchScheduler`, recording each `write` and `flush` so the schedule can be replayed against a fresh
+/// scheduler with `replay`, e.g. to reproduce a bug that depends on when indexes were updated.
+pub struct RecordingScheduler<T: Table<S>, I: Index<S, Source = T>, S: SeqType = Seq> {
+    scheduler: BatchScheduler<T, I, S>,
+    clock: Box<dyn FnMut() -> Duration>,
+    schedule: Schedule<T::Event>,
+}
+
+impl<T: Table<S>, I: Index<S, Source = T>, S: SeqType> RecordingScheduler<T, I, S>
+where
+    T::Event: Clone,
+{
+    /// Timestamps calls with the time elapsed since the recording began.
+    pub fn new(scheduler: BatchScheduler<T, I, S>) -> Self {
+        let start = Instant::now();
+        Self::with_clock(scheduler, move || start.elapsed())
+    }
+
+    /// Like `new`, but timestamps calls by reading `clock`, e.g. so tests are deterministic.
+    pub fn with_clock(
+        scheduler: BatchScheduler<T, I, S>, clock: impl FnMut() -> Duration + 'static,
+    ) -> Self {
+        Self { scheduler, clock: Box::new(clock), schedule: Vec::new() }
+    }
+
+    pub fn write<Iter: IntoIterator<Item = T::Event>>(&mut self, events: Iter) -> Vec<S> {
+        let events = events.into_iter().collect::<Vec<_>>();
+        self.record(ScheduledCall::Write(events.clone()));
+        self.scheduler.write(events)
+    }
+
+    pub fn flush(&mut self) {
+        self.record(ScheduledCall::Flush);
+        self.scheduler.flush();
+    }
+
+    /// Returns the calls recorded so far, in the order they were made.
+    pub fn schedule(&self) -> &[RecordedCall<T::Event>] {
+        &self.schedule
+    }
+
+    pub fn scheduler_mut(&mut self) -> &mut BatchScheduler<T, I, S> {
+        &mut self.scheduler
+    }
+
+    pub fn into_inner(self) -> (BatchScheduler<T, I, S>, Schedule<T::Event>) {
+        (self.scheduler, self.schedule)
+    }
+
+    fn record(&mut self, call: ScheduledCall<T::Event>) {
+        let at = (self.clock)();
+        self.schedule.push(RecordedCall { at, call });
+    }
+}
+
+/// Makes the recorded calls on `scheduler` in order. Timestamps are kept for inspection; calls are made back to back.
+pub fn replay<T: Table<S>, I: Index<S, Source = T>, S: SeqType>(
+    schedule: &[RecordedCall<T::Event>], scheduler: &mut BatchScheduler<T, I, S>,
+) where
+    T::Event: Clone,
+{
+    for recorded in schedule {
+        match &recorded.call {
+            ScheduledCall::Write(events) => {
+                scheduler.write(events.iter().cloned());
+            }
+            ScheduledCall::Flush => scheduler.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, BatchScheduler, RecordingScheduler, ScheduledCall};
+    use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+    use crate::index::multi_index::MultiIndex;
+    use crate::table::vec::VecTable;
+    use crate::{EventHandler, Index, Seq};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// Records the seq of each update.
+    #[derive(Default)]
+    struct UpdateLog {
+        current_seq: Seq,
+        updates: Vec<Seq>,
+    }
+
+    impl Index for UpdateLog {
+        type Source = VecTable<i32>;
+
+        fn update(&mut self, _source: &mut Self::Source, seq: Seq) {
+            self.current_seq = seq;
+            self.updates.push(seq);
+        }
+
+        fn get_current_seq(&self) -> Seq {
+            self.current_seq
+        }
+    }
+
+    #[test]
+    fn write() {
+        let mut scheduler = BatchScheduler::new(VecTable::new(), UpdateLog::default(), 3);
+        for event in 1..=5 {
+            scheduler.write([event]);
+        }
+        assert_eq!(scheduler.get_current_seq(), 5);
+        assert_eq!(scheduler.pending(), 2);
+        assert_eq!(scheduler.parts_mut().1.updates, vec![3]);
+
+        scheduler.flush();
+        assert_eq!(scheduler.pending(), 0);
+        let (_, index) = scheduler.into_inner();
+        assert_eq!(index.updates, vec![3, 5]);
+    }
+
+    #[test]
+    fn write_empty() {
+        let mut scheduler = BatchScheduler::new(VecTable::new(), UpdateLog::default(), 1);
+        assert!(scheduler.write([]).is_empty());
+        assert_eq!(scheduler.pending(), 0);
+        assert!(scheduler.parts_mut().1.updates.is_empty());
+
+        scheduler.write([12]);
+        scheduler.write([]);
+        let (_, index) = scheduler.into_inner();
+        assert_eq!(index.updates, vec![1]);
+    }
+
+    #[test]
+    fn write_batch() {
+        let index =
+            HashMapIndex::new(|(key, value): (&str, i32)| [HashMapUpdate::Insert { key, value }]);
+        let mut scheduler = BatchScheduler::new(VecTable::new(), index, 3);
+
+        assert_eq!(scheduler.write([("a", 1), ("b", 2)]), vec![1, 2]);
+        let (table, index) = scheduler.parts_mut();
+        assert_eq!(index.get_current_seq(), 0);
+        // reads past the index's current seq still see pending events
+        assert_eq!(index.get(table, 2, &"b"), Some(2));
+
+        // a batch past the threshold is flushed whole
+        assert_eq!(scheduler.write([("a", 3), ("c", 4)]), vec![3, 4]);
+        assert_eq!(scheduler.pending(), 0);
+        let (table, index) = scheduler.parts_mut();
+        assert_eq!(index.get_current_seq(), 4);
+        assert_eq!(index.get(table, 4, &"a"), Some(3));
+    }
+
+    /// Records each `(seq, event)` it handles.
+    struct Recorder(Rc<RefCell<Vec<(Seq, i32)>>>);
+
+    impl EventHandler<i32> for Recorder {
+        fn handle(&mut self, seq: Seq, event: &i32) {
+            self.0.borrow_mut().push((seq, *event));
+        }
+    }
+
+    #[test]
+    fn handlers() {
+        let mut scheduler = BatchScheduler::new(VecTable::new(), UpdateLog::default(), 2);
+        scheduler.write([12]);
+        let (first, second) = (Rc::default(), Rc::default());
+        scheduler.add_handler(Recorder(Rc::clone(&first)));
+        scheduler.add_handler(Recorder(Rc::clone(&second)));
+
+        // handlers run once the index is updated past an event, including events pending when they were added
+        scheduler.write([34]);
+        assert_eq!(*first.borrow(), vec![(1, 12), (2, 34)]);
+        scheduler.write([56]);
+        assert_eq!(first.borrow().len(), 2);
+        scheduler.write([78, 90]);
+        scheduler.flush();
+
+        let expected = vec![(1, 12), (2, 34), (3, 56), (4, 78), (5, 90)];
+        assert_eq!(*first.borrow(), expected);
+        assert_eq!(*second.borrow(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must be positive")]
+    fn zero_threshold() {
+        BatchScheduler::new(VecTable::<i32>::new(), UpdateLog::default(), 0);
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let index = || {
+            let by_parity = HashMapIndex::new(|event: i32| {
+                [HashMapUpdate::Insert { key: event % 2, value: event }]
+            });
+            MultiIndex::new((UpdateLog::default(), by_parity))
+        };
+        let mut now = Duration::ZERO;
+        let mut recording = RecordingScheduler::with_clock(
+            BatchScheduler::new(VecTable::new(), index(), 3),
+            move || {
+                now += Duration::from_millis(10);
+                now
+            },
+        );
+        recording.write([1, 2]);
+        recording.write([3, 4]);
+        recording.write([5]);
+        recording.flush();
+        recording.write([6]);
+
+        let (original, schedule) = recording.into_inner();
+        let calls = schedule
+            .iter()
+            .map(|recorded| (recorded.at.as_millis(), recorded.call.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            vec![
+                (10, ScheduledCall::Write(vec![1, 2])),
+                (20, ScheduledCall::Write(vec![3, 4])),
+                (30, ScheduledCall::Write(vec![5])),
+                (40, ScheduledCall::Flush),
+                (50, ScheduledCall::Write(vec![6])),
+            ]
+        );
+
+        let mut replayed = BatchScheduler::new(VecTable::new(), index(), 3);
+        replay(&schedule, &mut replayed);
+
+        let (mut original_table, original_index) = original.into_inner();
+        let (mut replayed_table, replayed_index) = replayed.into_inner();
+        let (original_log, original_by_parity) = original_index.indexes();
+        let (replayed_log, replayed_by_parity) = replayed_index.indexes();
+        assert_eq!(original_log.updates, vec![4, 5]);
+        assert_eq!(replayed_log.updates, original_log.updates);
+        for seq in 0..=6 {
+            assert_eq!(
+                replayed_by_parity.get_all(&mut replayed_table, seq),
+                original_by_parity.get_all(&mut original_table, seq)
+            );
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::error::Error;
+use crate::table::range_indices_by_key;
+use crate::{Seq, SeqType, Table, TryTable, View};
+
+/// A table whose events carry caller-assigned sequence numbers, e.g. when importing externally-sequenced data. Events
+/// are kept sorted by sequence number regardless of the order in which they're written.
+#[derive(Clone)]
+pub struct ExplicitSeqTable<Event> {
+    current_seq: Seq,
+    events: Vec<(Seq, Event)>,
+}
+
+impl<Event: Clone> ExplicitSeqTable<Event> {
+    pub fn new() -> Self {
+        ExplicitSeqTable { events: Vec::new(), current_seq: 0 }
+    }
+
+    /// Write the given event at the given sequence number. Appends if the sequence number is greater than all existing
+    /// ones, otherwise inserts at the sorted position (after any events with an equal sequence number).
+    pub fn append_at(&mut self, seq: Seq, event: Event) {
+        match self.events.last() {
+            Some((last_seq, _)) if *last_seq > seq => {
+                let idx = self.events.partition_point(|(s, _)| *s <= seq);
+                self.events.insert(idx, (seq, event));
+            }
+            _ => {
+                self.events.push((seq, event));
+            }
+        }
+        self.current_seq = self.current_seq.max(seq);
+    }
+}
+
+impl<Event: Clone> Default for ExplicitSeqTable<Event> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Event: Clone> View for ExplicitSeqTable<Event> {
+    type Event = Event;
+    type Iterator = ExplicitSeqTableIterator<Event>;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        ExplicitSeqTableIterator::new(self.clone(), start, end)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.current_seq
+    }
+
+    fn seq_upper_bound(&mut self) -> Seq {
+        self.current_seq
+    }
+}
+
+impl<Event: Clone> Table for ExplicitSeqTable<Event> {
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq> {
+        let mut result = Vec::new();
+        for event in events.into_iter() {
+            let seq = self
+                .current_seq
+                .checked_next()
+                .expect("sequence number overflow");
+            self.append_at(seq, event);
+            result.push(seq);
+        }
+        result
+    }
+
+    fn set_current_seq(&mut self, seq: Seq) {
+        self.current_seq = self.current_seq.max(seq);
+    }
+}
+
+impl<Event: Clone> TryTable for ExplicitSeqTable<Event> {
+    type Error = Error;
+
+    /// Like `append`, but returns an error instead of overflowing the sequence number, in which case nothing is written.
+    fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+        &mut self, events: Iter,
+    ) -> Result<Vec<Seq>, Self::Error> {
+        let events = events.into_iter().collect::<Vec<_>>();
+        if self.current_seq.checked_add(events.len() as Seq).is_none() {
+            return Err(Error::SeqOverflow);
+        }
+        Ok(self.append(events))
+    }
+}
+
+#[derive(Clone)]
+pub struct ExplicitSeqTableIterator<Event> {
+    table: ExplicitSeqTable<Event>,
+    min_idx_inclusive: usize,
+    max_idx_exclusive: usize,
+}
+
+impl<Event: Clone> ExplicitSeqTableIterator<Event> {
+    fn new(table: ExplicitSeqTable<Event>, min_seq_exclusive: Seq, max_seq_inclusive: Seq) -> Self {
+        let (min_idx, max_idx) = range_indices_by_key(
+            &table.events,
+            |(seq, _)| *seq,
+            min_seq_exclusive,
+            max_seq_inclusive,
+        );
+        Self { table, min_idx_inclusive: min_idx, max_idx_exclusive: max_idx }
+    }
+
+    fn next(&mut self) -> Option<(Seq, Event)> {
+        if self.min_idx_inclusive == self.max_idx_exclusive {
+            None
+        } else {
+            let result = self.table.events[self.min_idx_inclusive].clone();
+            self.min_idx_inclusive += 1;
+            Some(result)
+        }
+    }
+
+    fn next_back(&mut self) -> Option<(Seq, Event)> {
+        if self.min_idx_inclusive == self.max_idx_exclusive {
+            None
+        } else {
+            self.max_idx_exclusive -= 1; // decrementing before reference is what makes this exclusive
+            Some(self.table.events[self.max_idx_exclusive].clone())
+        }
+    }
+}
+
+impl<Event: Clone> Iterator for ExplicitSeqTableIterator<Event> {
+    type Item = (Seq, Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        ExplicitSeqTableIterator::<Event>::next(self)
+    }
+}
+
+impl<Event: Clone> DoubleEndedIterator for ExplicitSeqTableIterator<Event> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        ExplicitSeqTableIterator::<Event>::next_back(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExplicitSeqTable;
+    use crate::error::Error;
+    use crate::{Seq, Table, TryTable, View};
+
+    #[test]
+    fn scan_none() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        assert_eq!(table.get_current_seq(), 0);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn empty() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        assert_eq!(table.get_current_seq(), 0);
+        assert_eq!(table.first_seq(), None);
+        assert_eq!(table.scan(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn scan_beyond_current_seq() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(10, 12);
+        table.append_at(20, 34);
+        table.append_at(30, 56);
+        assert_eq!(table.get_current_seq(), 30);
+        assert_eq!(table.scan(10, Seq::MAX - 1).collect::<Vec<_>>(), vec![(20, 34), (30, 56)]);
+        assert_eq!(
+            table.scan(10, Seq::MAX - 1).collect::<Vec<_>>(),
+            table.scan(10, 30).collect::<Vec<_>>()
+        );
+        assert_eq!(table.scan(30, 1000 * 30).count(), 0);
+    }
+
+    #[test]
+    fn try_append_overflow() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.set_current_seq(Seq::MAX - 1);
+        assert!(matches!(table.try_append([12, 34]), Err(Error::SeqOverflow)));
+        assert_eq!(table.get_current_seq(), Seq::MAX - 1);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).count(), 0);
+        assert_eq!(table.try_append([12]).unwrap(), vec![Seq::MAX]);
+    }
+
+    #[test]
+    fn append_none() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(10, 12);
+        assert_eq!(table.append([]), Vec::<Seq>::new());
+        assert_eq!(table.get_current_seq(), 10);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(10, 12)]);
+    }
+
+    #[test]
+    fn scan_sparse() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(10, 12);
+        table.append_at(20, 34);
+        table.append_at(30, 56);
+        assert_eq!(table.get_current_seq(), 30);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(10, 12), (20, 34), (30, 56)]
+        );
+        assert_eq!(table.scan(10, 25).collect::<Vec<_>>(), vec![(20, 34)]);
+    }
+
+    #[test]
+    fn scan_out_of_order() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(30, 56);
+        table.append_at(10, 12);
+        table.append_at(40, 78);
+        table.append_at(20, 34);
+        assert_eq!(table.get_current_seq(), 40);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(10, 12), (20, 34), (30, 56), (40, 78)]
+        );
+    }
+
+    #[test]
+    fn scan_out_of_order_rev() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(30, 56);
+        table.append_at(10, 12);
+        table.append_at(40, 78);
+        table.append_at(20, 34);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(),
+            vec![(40, 78), (30, 56), (20, 34), (10, 12)]
+        );
+        assert_eq!(table.scan_rev(15, 35).collect::<Vec<_>>(), vec![(30, 56), (20, 34)]);
+        assert_eq!(table.scan(35, 15).count(), 0);
+    }
+
+    #[test]
+    fn append_after_import() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(20, 34);
+        table.append_at(10, 12);
+        assert_eq!(table.append([56, 78]), vec![21, 22]);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(10, 12), (20, 34), (21, 56), (22, 78)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sequence number overflow")]
+    fn append_overflow() {
+        let mut table = ExplicitSeqTable::<i32>::new();
+        table.append_at(Seq::MAX, 12);
+        table.append([34]);
+    }
+}
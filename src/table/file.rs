@@ -0,0 +1,464 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::table::offset_index::{BTreeOffsetIndex, OffsetIndex};
+use crate::{Seq, Table, TryTable, View};
+
+/// Each record is its event's sequence number (u64) and the length of its encoded event (u32), both little-endian,
+/// followed by the event encoded with bincode.
+const HEADER_LEN: usize = 12;
+
+/// An append-only table persisted to a file. Only the sequence number and file offset of each event are kept in memory;
+/// a scan reads each event from the file as it's iterated.
+///
+/// A record left half-written by a crash is truncated when the file is opened. The current sequence number is rebuilt
+/// from the last record, so one raised by `set_current_seq` past the last event isn't persisted.
+pub struct FileLog<Event> {
+    path: PathBuf,
+    file: File,
+    current_seq: Seq,
+    // the offset of each event's record, and the length of the file
+    offsets: BTreeOffsetIndex,
+    len: u64,
+    _event: PhantomData<fn() -> Event>,
+}
+
+impl<Event> FileLog<Event> {
+    /// Opens the log at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        // rebuild the in-memory index from the record headers
+        let mut current_seq = 0;
+        let mut offsets = BTreeOffsetIndex::new();
+        let mut len = 0;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(&file);
+        let mut header = [0; HEADER_LEN];
+        while file_len - len >= HEADER_LEN as u64 {
+            reader.read_exact(&mut header)?;
+            let (seq, event_len) = parse_header(&header);
+            let record_len = HEADER_LEN as u64 + event_len as u64;
+            if file_len - len < record_len {
+                // the final record was only partly written
+                break;
+            }
+            if len > 0 && seq <= current_seq {
+                return Err(Error::NonMonotonic { previous: current_seq, next: seq });
+            }
+            reader.seek_relative(event_len as i64)?;
+            offsets.insert(seq, len);
+            current_seq = seq;
+            len += record_len;
+        }
+        drop(reader);
+        if len < file_len {
+            file.set_len(len)?;
+        }
+
+        Ok(Self { path, file, current_seq, offsets, len, _event: PhantomData })
+    }
+
+    /// Returns the number of bytes the records of events between the given sequences occupy in the file.
+    pub fn byte_size(&self, start_exclusive: Seq, end_inclusive: Seq) -> u64 {
+        self.offsets
+            .byte_size(start_exclusive, end_inclusive, self.len)
+    }
+}
+
+fn parse_header(header: &[u8; HEADER_LEN]) -> (Seq, u32) {
+    let seq = Seq::from_le_bytes(header[..8].try_into().unwrap());
+    let event_len = u32::from_le_bytes(header[8..].try_into().unwrap());
+    (seq, event_len)
+}
+
+impl<Event: DeserializeOwned> View for FileLog<Event> {
+    type Event = Event;
+    type Iterator = FileLogIterator<Event>;
+
+    /// Looks up the offsets of the events in range, then reads and decodes each event from the file as it's iterated,
+    /// through a reader of its own. See `FileLogIterator::error` for errors reading the file.
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        let records = self.offsets.range(start, end);
+        FileLogIterator {
+            path: self.path.clone(),
+            reader: None,
+            pos: 0,
+            front: 0,
+            back: records.len(),
+            records,
+            error: None,
+            _event: PhantomData,
+        }
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.current_seq
+    }
+
+    fn seq_upper_bound(&mut self) -> Seq {
+        self.current_seq
+    }
+}
+
+impl<Event: Serialize + DeserializeOwned> TryTable for FileLog<Event> {
+    type Error = Error;
+
+    /// Writes and flushes the events' records. On error nothing is written: a partial write is truncated.
+    fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+        &mut self, events: Iter,
+    ) -> Result<Vec<Seq>, Self::Error> {
+        let mut buf = Vec::new();
+        let mut result = Vec::new();
+        let mut offsets = Vec::new();
+        let mut seq = self.current_seq;
+        for event in events {
+            seq = seq.checked_add(1).ok_or(Error::SeqOverflow)?;
+            let encoded = bincode::serialize(&event).map_err(io::Error::other)?;
+            let event_len = u32::try_from(encoded.len()).map_err(io::Error::other)?;
+            offsets.push((seq, self.len + buf.len() as u64));
+            buf.extend_from_slice(&seq.to_le_bytes());
+            buf.extend_from_slice(&event_len.to_le_bytes());
+            buf.extend_from_slice(&encoded);
+            result.push(seq);
+        }
+        if result.is_empty() {
+            return Ok(result);
+        }
+
+        let written = self
+            .file
+            .seek(SeekFrom::Start(self.len))
+            .and_then(|_| self.file.write_all(&buf))
+            .and_then(|_| self.file.flush());
+        if let Err(err) = written {
+            // best effort: a record left half-written would also be truncated when the file is next opened
+            let _ = self.file.set_len(self.len);
+            return Err(err.into());
+        }
+
+        self.current_seq = seq;
+        for (seq, offset) in offsets {
+            self.offsets.insert(seq, offset);
+        }
+        self.len += buf.len() as u64;
+        Ok(result)
+    }
+}
+
+impl<Event: Serialize + DeserializeOwned> Table for FileLog<Event> {
+    /// Panics if the events can't be written; use `try_append` to handle the error instead.
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq> {
+        self.try_append(events)
+            .unwrap_or_else(|err| panic!("failed to append to file log: {}", err))
+    }
+
+    fn set_current_seq(&mut self, seq: Seq) {
+        self.current_seq = self.current_seq.max(seq);
+    }
+}
+
+/// Reads a scan's events from the file one record at a time, through a buffered reader opened on the first read. If
+/// the file can't be read or a record can't be decoded, iteration ends early and `error` returns the cause.
+pub struct FileLogIterator<Event> {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+    // the reader's offset in the file
+    pos: u64,
+    // the sequence number and offset of each record in range
+    records: Vec<(Seq, u64)>,
+    // the range of records not yet iterated
+    front: usize,
+    back: usize,
+    error: Option<Error>,
+    _event: PhantomData<fn() -> Event>,
+}
+
+impl<Event: DeserializeOwned> FileLogIterator<Event> {
+    /// Returns the error that ended iteration early, if any.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Reads and decodes the record at `offset`, seeking only if the reader isn't already there, as it is when
+    /// iterating forwards.
+    fn read(&mut self, offset: u64) -> Result<Event, Error> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => self.reader.insert(BufReader::new(File::open(&self.path)?)),
+        };
+        if self.pos != offset {
+            reader.seek(SeekFrom::Start(offset))?;
+        }
+
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let (_, event_len) = parse_header(&header);
+        let mut event_bytes = vec![0; event_len as usize];
+        reader.read_exact(&mut event_bytes)?;
+        self.pos = offset + (HEADER_LEN + event_bytes.len()) as u64;
+
+        bincode::deserialize(&event_bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+    }
+
+    /// Reads the record at `idx`, or on error, records it and ends iteration.
+    fn next_at(&mut self, idx: usize) -> Option<(Seq, Event)> {
+        let (seq, offset) = self.records[idx];
+        match self.read(offset) {
+            Ok(event) => Some((seq, event)),
+            Err(err) => {
+                // a reader in an unknown position can't be trusted for later records
+                self.reader = None;
+                self.front = self.back;
+                self.error = Some(err);
+                None
+            }
+        }
+    }
+
+    fn next_lowest(&mut self) -> Option<(Seq, Event)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.next_at(self.front - 1)
+    }
+
+    fn next_highest(&mut self) -> Option<(Seq, Event)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.next_at(self.back)
+    }
+}
+
+impl<Event: DeserializeOwned> Iterator for FileLogIterator<Event> {
+    type Item = (Seq, Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_lowest()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // an error can end iteration early
+        (0, Some(self.back - self.front))
+    }
+}
+
+impl<Event: DeserializeOwned> DoubleEndedIterator for FileLogIterator<Event> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_highest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileLog;
+    use crate::error::Error;
+    use crate::{Seq, Table, TryTable, View};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    /// A path in the temp directory unique to the test, removed when dropped.
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "parasol-db-file-log-{}-{}",
+                std::process::id(),
+                name
+            ));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn reopen() {
+        let path = TempPath::new("reopen");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(log.get_current_seq(), 0);
+        assert_eq!(log.append(["a".to_string(), "b".to_string()]), vec![1, 2]);
+        log.set_current_seq(4);
+        assert_eq!(log.append(["c".to_string()]), vec![5]);
+        assert_eq!(log.append(Vec::<String>::new()), Vec::<Seq>::new());
+        drop(log);
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(log.get_current_seq(), 5);
+        assert_eq!(
+            log.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, "a".to_string()), (2, "b".to_string()), (5, "c".to_string())]
+        );
+        assert_eq!(
+            log.scan_rev(1, 5).collect::<Vec<_>>(),
+            vec![(5, "c".to_string()), (2, "b".to_string())]
+        );
+        assert_eq!(log.scan(5, Seq::MAX).count(), 0);
+        assert_eq!(log.scan(5, 1).count(), 0);
+
+        let mut iter = log.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((1, "a".to_string())));
+        assert_eq!(iter.next_back(), Some((5, "c".to_string())));
+        assert_eq!(iter.next_back(), Some((2, "b".to_string())));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(log.append(["d".to_string()]), vec![6]);
+        assert_eq!(
+            log.scan(2, Seq::MAX).collect::<Vec<_>>(),
+            vec![(5, "c".to_string()), (6, "d".to_string())]
+        );
+    }
+
+    #[test]
+    fn truncate_half_written_record() {
+        let path = TempPath::new("truncate_half_written_record");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        log.append(["a".to_string(), "b".to_string()]);
+        drop(log);
+        let len = std::fs::metadata(&path.0).unwrap().len();
+
+        // a crash partway through writing a record's event
+        let mut file = OpenOptions::new().append(true).open(&path.0).unwrap();
+        file.write_all(&3u64.to_le_bytes()).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(b"partial").unwrap();
+        drop(file);
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(std::fs::metadata(&path.0).unwrap().len(), len);
+        assert_eq!(log.get_current_seq(), 2);
+        assert_eq!(log.append(["c".to_string()]), vec![3]);
+        drop(log);
+
+        // a crash partway through writing a record's header
+        let mut file = OpenOptions::new().append(true).open(&path.0).unwrap();
+        file.write_all(&4u64.to_le_bytes()).unwrap();
+        drop(file);
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(
+            log.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, "a".to_string()), (2, "b".to_string()), (3, "c".to_string())]
+        );
+    }
+
+    #[test]
+    fn open_non_monotonic() {
+        let path = TempPath::new("open_non_monotonic");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        log.append(["a".to_string(), "b".to_string()]);
+        drop(log);
+
+        // a record whose seq doesn't follow the last one's
+        let mut file = OpenOptions::new().append(true).open(&path.0).unwrap();
+        let encoded = bincode::serialize(&"c".to_string()).unwrap();
+        file.write_all(&1u64.to_le_bytes()).unwrap();
+        file.write_all(&(encoded.len() as u32).to_le_bytes())
+            .unwrap();
+        file.write_all(&encoded).unwrap();
+        drop(file);
+
+        assert!(matches!(
+            FileLog::<String>::open(&path.0),
+            Err(Error::NonMonotonic { previous: 2, next: 1 })
+        ));
+    }
+
+    #[test]
+    fn open_io_error() {
+        let path = TempPath::new("open_io_error");
+        assert!(matches!(FileLog::<String>::open(path.0.join("log")), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn try_append_overflow() {
+        let path = TempPath::new("try_append_overflow");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        log.append(["a".to_string()]);
+        log.set_current_seq(Seq::MAX - 1);
+        assert!(matches!(
+            log.try_append(["b".to_string(), "c".to_string()]),
+            Err(Error::SeqOverflow)
+        ));
+
+        // nothing is written
+        assert_eq!(log.get_current_seq(), Seq::MAX - 1);
+        drop(log);
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(log.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, "a".to_string())]);
+    }
+
+    #[test]
+    fn byte_size() {
+        let path = TempPath::new("byte_size");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        assert_eq!(log.byte_size(Seq::MIN, Seq::MAX), 0);
+        log.append(["a".to_string(), "bc".to_string()]);
+        log.set_current_seq(4);
+        log.append(["def".to_string()]);
+
+        // each record is a 12 byte header and a string encoded as its u64 length and bytes
+        let len = std::fs::metadata(&path.0).unwrap().len();
+        assert_eq!(log.byte_size(Seq::MIN, Seq::MAX), len);
+        assert_eq!(log.byte_size(0, 1), 12 + 8 + 1);
+        assert_eq!(log.byte_size(1, 4), 12 + 8 + 2);
+        assert_eq!(log.byte_size(2, 5), 12 + 8 + 3);
+        assert_eq!(log.byte_size(5, Seq::MAX), 0);
+        assert_eq!(log.byte_size(5, 1), 0);
+    }
+
+    #[test]
+    fn scan_read_error() {
+        let path = TempPath::new("scan_read_error");
+
+        let mut log = FileLog::<String>::open(&path.0).unwrap();
+        log.append(["a".to_string(), "b".to_string(), "c".to_string()]);
+        let mut iter = log.scan(Seq::MIN, Seq::MAX);
+        let mut iter_rev = log.scan(Seq::MIN, Seq::MAX);
+
+        // events are read as they're iterated, so a scan sees the file as it is then
+        let len = log.byte_size(0, 1) + 1;
+        OpenOptions::new()
+            .write(true)
+            .open(&path.0)
+            .unwrap()
+            .set_len(len)
+            .unwrap();
+        assert_eq!(iter.next(), Some((1, "a".to_string())));
+        assert!(iter.error().is_none());
+        assert_eq!(iter.next(), None);
+        assert!(matches!(iter.error(), Some(Error::Io(_))));
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!(iter_rev.next_back(), None);
+        assert!(matches!(iter_rev.error(), Some(Error::Io(_))));
+    }
+}
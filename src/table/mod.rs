@@ -1 +1,61 @@
+pub mod explicit_seq;
+#[cfg(feature = "file")]
+pub mod file;
+pub mod offset_index;
+pub mod retry;
+pub mod ring;
+pub mod shared;
 pub mod vec;
+
+/// Returns the index range `min..max` of the sorted `seqs` that fall after `min_exclusive` and at or before
+/// `max_inclusive`. The range is empty, not inverted, if `min_exclusive` is past `max_inclusive`. Partition points rather
+/// than binary searches keep equal sequence numbers (e.g. in a merged table) together.
+pub(crate) fn range_indices<S: Ord + Copy>(
+    seqs: &[S], min_exclusive: S, max_inclusive: S,
+) -> (usize, usize) {
+    range_indices_by_key(seqs, |seq| *seq, min_exclusive, max_inclusive)
+}
+
+/// Like `range_indices`, but for items sorted by the sequence number `key` returns, e.g. `(seq, event)` pairs.
+pub(crate) fn range_indices_by_key<T, S: Ord>(
+    items: &[T], key: impl Fn(&T) -> S, min_exclusive: S, max_inclusive: S,
+) -> (usize, usize) {
+    let min = items.partition_point(|item| key(item) <= min_exclusive);
+    let max = items
+        .partition_point(|item| key(item) <= max_inclusive)
+        .max(min);
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::range_indices;
+    use crate::Seq;
+
+    #[test]
+    fn range_indices_empty() {
+        assert_eq!(range_indices::<Seq>(&[], Seq::MIN, Seq::MAX), (0, 0));
+        assert_eq!(range_indices::<Seq>(&[], 5, 1), (0, 0));
+    }
+
+    #[test]
+    fn range_indices_full() {
+        assert_eq!(range_indices(&[1, 2, 3], Seq::MIN, Seq::MAX), (0, 3));
+        assert_eq!(range_indices(&[1, 2, 3], 0, 3), (0, 3));
+    }
+
+    #[test]
+    fn range_indices_partial() {
+        assert_eq!(range_indices(&[1, 2, 3, 4], 1, 3), (1, 3));
+        assert_eq!(range_indices(&[1, 2, 2, 4], 1, 2), (1, 3));
+        assert_eq!(range_indices(&[2, 4, 6], 3, 5), (1, 2));
+        assert_eq!(range_indices(&[1, 2, 3], 2, 2), (2, 2));
+    }
+
+    #[test]
+    fn range_indices_out_of_range() {
+        assert_eq!(range_indices(&[3, 4], 0, 2), (0, 0));
+        assert_eq!(range_indices(&[3, 4], 4, Seq::MAX), (2, 2));
+        assert_eq!(range_indices(&[3, 4], 4, 3), (2, 2));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use crate::Seq;
+
+/// Maps sequence numbers to byte offsets for file-backed tables. Implementations trade memory for lookup cost.
+pub trait OffsetIndex {
+    /// Records the byte offset of the event with the given sequence number.
+    fn insert(&mut self, seq: Seq, offset: u64);
+
+    /// Returns the byte offset of the event with the given sequence number, if there is one.
+    fn lookup(&self, seq: Seq) -> Option<u64>;
+
+    /// Returns the sequence numbers and byte offsets of events between the given sequences, in sequence order.
+    fn range(&self, start_exclusive: Seq, end_inclusive: Seq) -> Vec<(Seq, u64)>;
+
+    /// Returns the number of bytes occupied by the framed records of events between the given sequences, computed from
+    /// offsets alone. `len` is the offset just past the last record, e.g. the length of the file. An empty or inverted
+    /// range occupies no bytes.
+    fn byte_size(&self, start_exclusive: Seq, end_inclusive: Seq, len: u64) -> u64 {
+        let Some(&(_, start_offset)) = self.range(start_exclusive, end_inclusive).first() else {
+            return 0;
+        };
+        // the range ends where the next record starts, or at the end of the data if there's no next record
+        let end_offset = match self.range(end_inclusive, Seq::MAX).first() {
+            Some(&(_, offset)) => offset,
+            None => len,
+        };
+        end_offset.saturating_sub(start_offset)
+    }
+}
+
+/// Stores an offset slot for every sequence number between the lowest and highest inserted. Lookups are a single
+/// index, but memory grows with the gaps between sequence numbers, so this suits contiguous sequences.
+#[derive(Clone, Default)]
+pub struct VecOffsetIndex {
+    first_seq: Seq,
+    offsets: Vec<Option<u64>>,
+}
+
+impl VecOffsetIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OffsetIndex for VecOffsetIndex {
+    fn insert(&mut self, seq: Seq, offset: u64) {
+        if self.offsets.is_empty() {
+            self.first_seq = seq;
+        } else if seq < self.first_seq {
+            // shift existing slots up to make room below them
+            let gap = (self.first_seq - seq) as usize;
+            self.offsets.splice(0..0, std::iter::repeat_n(None, gap));
+            self.first_seq = seq;
+        }
+
+        let idx = (seq - self.first_seq) as usize;
+        if idx >= self.offsets.len() {
+            self.offsets.resize(idx + 1, None);
+        }
+        self.offsets[idx] = Some(offset);
+    }
+
+    fn lookup(&self, seq: Seq) -> Option<u64> {
+        let idx = seq.checked_sub(self.first_seq)? as usize;
+        self.offsets.get(idx).copied().flatten()
+    }
+
+    fn range(&self, start_exclusive: Seq, end_inclusive: Seq) -> Vec<(Seq, u64)> {
+        // clamp the bounds to the slots we have
+        let min_idx = start_exclusive
+            .saturating_add(1)
+            .saturating_sub(self.first_seq) as usize;
+        let max_idx = (end_inclusive
+            .saturating_add(1)
+            .saturating_sub(self.first_seq) as usize)
+            .min(self.offsets.len());
+        if min_idx >= max_idx {
+            return Vec::new();
+        }
+
+        self.offsets[min_idx..max_idx]
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, offset)| {
+                offset.map(|offset| (self.first_seq + (min_idx + idx) as Seq, offset))
+            })
+            .collect()
+    }
+}
+
+/// Stores offsets only for inserted sequence numbers. Lookups are logarithmic, but memory is independent of the gaps
+/// between sequence numbers, so this suits sparse sequences.
+#[derive(Clone, Default)]
+pub struct BTreeOffsetIndex {
+    offsets: BTreeMap<Seq, u64>,
+}
+
+impl BTreeOffsetIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OffsetIndex for BTreeOffsetIndex {
+    fn insert(&mut self, seq: Seq, offset: u64) {
+        self.offsets.insert(seq, offset);
+    }
+
+    fn lookup(&self, seq: Seq) -> Option<u64> {
+        self.offsets.get(&seq).copied()
+    }
+
+    fn range(&self, start_exclusive: Seq, end_inclusive: Seq) -> Vec<(Seq, u64)> {
+        if start_exclusive >= end_inclusive {
+            return Vec::new();
+        }
+
+        self.offsets
+            .range(start_exclusive + 1..=end_inclusive)
+            .map(|(seq, offset)| (*seq, *offset))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BTreeOffsetIndex, OffsetIndex, VecOffsetIndex};
+    use crate::Seq;
+
+    fn contiguous(index: &mut impl OffsetIndex) {
+        index.insert(1, 0);
+        index.insert(2, 12);
+        index.insert(3, 34);
+        index.insert(4, 56);
+
+        assert_eq!(index.lookup(0), None);
+        assert_eq!(index.lookup(1), Some(0));
+        assert_eq!(index.lookup(4), Some(56));
+        assert_eq!(index.lookup(5), None);
+        assert_eq!(index.range(Seq::MIN, Seq::MAX), vec![(1, 0), (2, 12), (3, 34), (4, 56)]);
+        assert_eq!(index.range(1, 3), vec![(2, 12), (3, 34)]);
+        assert_eq!(index.range(4, 4), vec![]);
+    }
+
+    fn sparse(index: &mut impl OffsetIndex) {
+        index.insert(20, 12);
+        index.insert(10, 0);
+        index.insert(40, 34);
+
+        assert_eq!(index.lookup(10), Some(0));
+        assert_eq!(index.lookup(20), Some(12));
+        assert_eq!(index.lookup(30), None);
+        assert_eq!(index.lookup(40), Some(34));
+        assert_eq!(index.range(Seq::MIN, Seq::MAX), vec![(10, 0), (20, 12), (40, 34)]);
+        assert_eq!(index.range(10, 39), vec![(20, 12)]);
+        assert_eq!(index.range(0, 5), vec![]);
+    }
+
+    // records of sizes 10, 20, 30, and 40 bytes
+    fn byte_size(index: &mut impl OffsetIndex) {
+        index.insert(1, 0);
+        index.insert(2, 10);
+        index.insert(5, 30);
+        index.insert(6, 60);
+        let len = 100;
+
+        assert_eq!(index.byte_size(Seq::MIN, Seq::MAX, len), 100);
+        assert_eq!(index.byte_size(0, 1, len), 10);
+        assert_eq!(index.byte_size(1, 5, len), 50);
+        assert_eq!(index.byte_size(2, 4, len), 0);
+        assert_eq!(index.byte_size(5, 6, len), 40);
+        assert_eq!(index.byte_size(6, Seq::MAX, len), 0);
+
+        // reversed ranges are empty rather than underflowing
+        assert_eq!(index.byte_size(5, 1, len), 0);
+        assert_eq!(index.byte_size(Seq::MAX, Seq::MIN, len), 0);
+    }
+
+    #[test]
+    fn vec_byte_size() {
+        byte_size(&mut VecOffsetIndex::new());
+    }
+
+    #[test]
+    fn btree_byte_size() {
+        byte_size(&mut BTreeOffsetIndex::new());
+    }
+
+    #[test]
+    fn vec_contiguous() {
+        contiguous(&mut VecOffsetIndex::new());
+    }
+
+    #[test]
+    fn vec_sparse() {
+        sparse(&mut VecOffsetIndex::new());
+    }
+
+    #[test]
+    fn btree_contiguous() {
+        contiguous(&mut BTreeOffsetIndex::new());
+    }
+
+    #[test]
+    fn btree_sparse() {
+        sparse(&mut BTreeOffsetIndex::new());
+    }
+}
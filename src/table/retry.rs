@@ -0,0 +1,224 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::{Seq, TryTable, View};
+
+/// The longest a `RetryTable` created with `new` sleeps between attempts.
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Retries failed writes to the inner table up to `max_attempts` times, sleeping between attempts with a backoff that
+/// starts at `backoff` and doubles after each failure, up to `max_backoff`. A zero backoff never sleeps.
+///
+/// Retrying rewrites the whole batch, so it relies on the inner table's `try_append` being all-or-nothing, as `TryTable`
+/// requires; `FileLog` truncates a partial write before returning its error.
+pub struct RetryTable<Inner: TryTable> {
+    inner: Inner,
+    max_attempts: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    sleep: Box<dyn Fn(Duration) + Send>,
+    last_attempts: usize,
+}
+
+impl<Inner: TryTable> RetryTable<Inner> {
+    pub fn new(inner: Inner, max_attempts: usize, backoff: Duration) -> Self {
+        Self::with_max_backoff(inner, max_attempts, backoff, DEFAULT_MAX_BACKOFF)
+    }
+
+    /// Like `new`, but capping the backoff at `max_backoff` rather than `DEFAULT_MAX_BACKOFF`.
+    pub fn with_max_backoff(
+        inner: Inner, max_attempts: usize, backoff: Duration, max_backoff: Duration,
+    ) -> Self {
+        Self::with_sleep(inner, max_attempts, backoff, max_backoff, thread::sleep)
+    }
+
+    /// Like `with_max_backoff`, but sleeping by calling `sleep` rather than `thread::sleep`, e.g. so tests can check the
+    /// backoff without waiting for it.
+    pub fn with_sleep(
+        inner: Inner, max_attempts: usize, backoff: Duration, max_backoff: Duration,
+        sleep: impl Fn(Duration) + Send + 'static,
+    ) -> Self {
+        Self {
+            inner,
+            max_attempts: max_attempts.max(1),
+            backoff: backoff.min(max_backoff),
+            max_backoff,
+            sleep: Box::new(sleep),
+            last_attempts: 0,
+        }
+    }
+
+    /// Returns the backoff after `failures` failed attempts: doubled for each, saturating rather than overflowing on
+    /// long retry sequences, and capped at the maximum.
+    fn backoff_after(&self, failures: usize) -> Duration {
+        let factor = 2u32.saturating_pow(failures.try_into().unwrap_or(u32::MAX));
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Returns the number of attempts made by the most recent write, including the successful one if any.
+    pub fn last_attempts(&self) -> usize {
+        self.last_attempts
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+}
+
+impl<Inner: TryTable> View for RetryTable<Inner> {
+    type Event = Inner::Event;
+    type Iterator = Inner::Iterator;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        self.inner.scan(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+
+    fn seq_upper_bound(&mut self) -> Seq {
+        self.inner.seq_upper_bound()
+    }
+}
+
+impl<Inner: TryTable> TryTable for RetryTable<Inner>
+where
+    Inner::Event: Clone,
+{
+    type Error = Inner::Error;
+
+    /// Returns the error from the final attempt if every attempt failed.
+    fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+        &mut self, events: Iter,
+    ) -> Result<Vec<Seq>, Self::Error> {
+        // events are buffered so they can be rewritten on retry
+        let events = events.into_iter().collect::<Vec<_>>();
+        self.last_attempts = 0;
+        loop {
+            self.last_attempts += 1;
+            match self.inner.try_append(events.iter().cloned()) {
+                Ok(seqs) => return Ok(seqs),
+                Err(err) if self.last_attempts >= self.max_attempts => return Err(err),
+                Err(_) => {
+                    let backoff = self.backoff_after(self.last_attempts - 1);
+                    if !backoff.is_zero() {
+                        (self.sleep)(backoff);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{RetryTable, DEFAULT_MAX_BACKOFF};
+    use crate::table::vec::{VecTable, VecTableIterator};
+    use crate::{Seq, Table, TryTable, View};
+
+    /// Fails the given number of writes before delegating to a `VecTable`.
+    struct FlakyTable {
+        table: VecTable<i32>,
+        failures: usize,
+    }
+
+    impl View for FlakyTable {
+        type Event = i32;
+        type Iterator = VecTableIterator<i32>;
+
+        fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+            self.table.scan(start_exclusive, end_inclusive)
+        }
+
+        fn get_current_seq(&mut self) -> Seq {
+            self.table.get_current_seq()
+        }
+    }
+
+    impl TryTable for FlakyTable {
+        type Error = &'static str;
+
+        fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+            &mut self, events: Iter,
+        ) -> Result<Vec<Seq>, Self::Error> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                Err("transient failure")
+            } else {
+                Ok(self.table.append(events))
+            }
+        }
+    }
+
+    #[test]
+    fn retry_until_success() {
+        let flaky = FlakyTable { table: VecTable::new(), failures: 2 };
+        let mut table = RetryTable::new(flaky, 3, Duration::ZERO);
+
+        assert_eq!(table.try_append([12, 34]), Ok(vec![1, 2]));
+        assert_eq!(table.last_attempts(), 3);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+
+        assert_eq!(table.try_append([56]), Ok(vec![3]));
+        assert_eq!(table.last_attempts(), 1);
+    }
+
+    #[test]
+    fn backoff() {
+        let flaky = FlakyTable { table: VecTable::new(), failures: 0 };
+        let table = RetryTable::with_max_backoff(
+            flaky,
+            usize::MAX,
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        );
+        assert_eq!(table.backoff_after(0), Duration::from_millis(10));
+        assert_eq!(table.backoff_after(1), Duration::from_millis(20));
+        assert_eq!(table.backoff_after(2), Duration::from_millis(40));
+        assert_eq!(table.backoff_after(3), Duration::from_millis(50));
+
+        // long retry sequences saturate rather than overflow
+        assert_eq!(table.backoff_after(64), Duration::from_millis(50));
+        assert_eq!(table.backoff_after(usize::MAX), Duration::from_millis(50));
+
+        let flaky = FlakyTable { table: VecTable::new(), failures: 0 };
+        let table = RetryTable::new(flaky, 3, Duration::MAX);
+        assert_eq!(table.backoff_after(1), DEFAULT_MAX_BACKOFF);
+    }
+
+    #[test]
+    fn sleep_between_attempts() {
+        let flaky = FlakyTable { table: VecTable::new(), failures: 3 };
+        let sleeps = Arc::new(Mutex::new(Vec::new()));
+        let mut table = RetryTable::with_sleep(
+            flaky,
+            4,
+            Duration::from_millis(10),
+            Duration::from_millis(25),
+            {
+                let sleeps = Arc::clone(&sleeps);
+                move |duration| sleeps.lock().unwrap().push(duration)
+            },
+        );
+
+        assert_eq!(table.try_append([12]), Ok(vec![1]));
+        assert_eq!(
+            *sleeps.lock().unwrap(),
+            vec![Duration::from_millis(10), Duration::from_millis(20), Duration::from_millis(25)]
+        );
+    }
+
+    #[test]
+    fn retry_exhausted() {
+        let flaky = FlakyTable { table: VecTable::new(), failures: 3 };
+        let mut table = RetryTable::new(flaky, 3, Duration::ZERO);
+
+        assert_eq!(table.try_append([12]), Err("transient failure"));
+        assert_eq!(table.last_attempts(), 3);
+        assert_eq!(table.get_current_seq(), 0);
+    }
+}
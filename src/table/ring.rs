@@ -0,0 +1,258 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::table::range_indices_by_key;
+use crate::{Seq, Table, View};
+
+/// An in-memory table that keeps only its most recent `capacity` events, e.g. for metrics where only recent history
+/// matters. Once full, each append evicts the oldest event, but sequence numbers keep increasing, so the current sequence
+/// number counts evicted events too and scans return only the events still resident. Like `VecTable`, its storage is
+/// shared copy-on-write with live scans.
+#[derive(Clone)]
+pub struct RingTable<Event> {
+    capacity: usize,
+    current_seq: Seq,
+    events: Arc<VecDeque<(Seq, Event)>>,
+    // the seq of the last evicted event
+    evicted_through: Seq,
+}
+
+impl<Event: Clone> RingTable<Event> {
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            capacity,
+            current_seq: 0,
+            events: Arc::new(VecDeque::with_capacity(capacity)),
+            evicted_through: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of resident events, at most `capacity`.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Like `scan`, but returns `Error::BelowRetention` if the range includes evicted events, rather than silently
+    /// returning only the resident ones.
+    pub fn try_scan(&mut self, start: Seq, end: Seq) -> Result<RingTableIterator<Event>, Error> {
+        if start < self.evicted_through && start < end {
+            return Err(Error::BelowRetention {
+                seq: start + 1,
+                first_retained: self.evicted_through + 1,
+            });
+        }
+        Ok(self.scan(start, end))
+    }
+}
+
+impl<Event: Clone> View for RingTable<Event> {
+    type Event = Event;
+    type Iterator = RingTableIterator<Event>;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        // evicted events are simply absent, like events before a `VecTable`'s retention point. The deque's two slices are
+        // each sorted and the back one follows the front one, so an index falls in the back slice only if it's past the
+        // whole front slice.
+        let (front, back) = self.events.as_slices();
+        let key = |(seq, _): &(Seq, Event)| *seq;
+        let (front_min, front_max) = range_indices_by_key(front, key, start, end);
+        let (back_min, back_max) = range_indices_by_key(back, key, start, end);
+        let combine = |front_idx: usize, back_idx: usize| {
+            if front_idx < front.len() {
+                front_idx
+            } else {
+                front.len() + back_idx
+            }
+        };
+        let (min_idx, max_idx) = (combine(front_min, back_min), combine(front_max, back_max));
+        RingTableIterator {
+            events: Arc::clone(&self.events),
+            min_idx_inclusive: min_idx,
+            max_idx_exclusive: max_idx,
+        }
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.current_seq
+    }
+
+    fn seq_upper_bound(&mut self) -> Seq {
+        self.current_seq
+    }
+
+    fn first_seq(&mut self) -> Option<Seq> {
+        self.events.front().map(|(seq, _)| *seq)
+    }
+}
+
+impl<Event: Clone> Table for RingTable<Event> {
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq> {
+        let table_events = Arc::make_mut(&mut self.events);
+        let mut result = Vec::new();
+        for event in events.into_iter() {
+            self.current_seq = self
+                .current_seq
+                .checked_add(1)
+                .expect("sequence number overflow");
+            result.push(self.current_seq);
+            if table_events.len() == self.capacity {
+                if let Some((seq, _)) = table_events.pop_front() {
+                    self.evicted_through = seq;
+                }
+            }
+            table_events.push_back((self.current_seq, event));
+        }
+        result
+    }
+
+    fn set_current_seq(&mut self, seq: Seq) {
+        self.current_seq = self.current_seq.max(seq);
+    }
+}
+
+#[derive(Clone)]
+pub struct RingTableIterator<Event> {
+    events: Arc<VecDeque<(Seq, Event)>>,
+    min_idx_inclusive: usize,
+    max_idx_exclusive: usize,
+}
+
+impl<Event: Clone> RingTableIterator<Event> {
+    fn next_front(&mut self) -> Option<(Seq, Event)> {
+        if self.min_idx_inclusive == self.max_idx_exclusive {
+            None
+        } else {
+            let result = self.events[self.min_idx_inclusive].clone();
+            self.min_idx_inclusive += 1;
+            Some(result)
+        }
+    }
+
+    fn next_back(&mut self) -> Option<(Seq, Event)> {
+        if self.min_idx_inclusive == self.max_idx_exclusive {
+            None
+        } else {
+            self.max_idx_exclusive -= 1;
+            Some(self.events[self.max_idx_exclusive].clone())
+        }
+    }
+}
+
+impl<Event: Clone> Iterator for RingTableIterator<Event> {
+    type Item = (Seq, Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.max_idx_exclusive - self.min_idx_inclusive;
+        (len, Some(len))
+    }
+}
+
+impl<Event: Clone> ExactSizeIterator for RingTableIterator<Event> {}
+
+impl<Event: Clone> DoubleEndedIterator for RingTableIterator<Event> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        RingTableIterator::next_back(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingTable;
+    use crate::error::Error;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan_below_capacity() {
+        let mut table = RingTable::<i32>::new(3);
+        assert_eq!(table.append([12, 34]), vec![1, 2]);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+        assert_eq!(table.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(2, 34), (1, 12)]);
+    }
+
+    #[test]
+    fn eviction() {
+        let mut table = RingTable::<i32>::new(3);
+        assert_eq!(table.append([12, 34, 56, 78, 90]), vec![1, 2, 3, 4, 5]);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get_current_seq(), 5);
+        assert_eq!(table.first_seq(), Some(3));
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(3, 56), (4, 78), (5, 90)]
+        );
+
+        // the evicted prefix of a range is empty
+        assert_eq!(table.scan(0, 3).collect::<Vec<_>>(), vec![(3, 56)]);
+        assert_eq!(table.scan(1, 4).collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+        assert_eq!(table.scan_rev(1, 4).collect::<Vec<_>>(), vec![(4, 78), (3, 56)]);
+        assert_eq!(table.scan(0, 2).count(), 0);
+        assert_eq!(table.scan(4, 1).count(), 0);
+        assert_eq!(table.get_event(2), None);
+        assert_eq!(table.get_event(4), Some((4, 78)));
+
+        assert!(matches!(
+            table.try_scan(0, 4),
+            Err(Error::BelowRetention { seq: 1, first_retained: 3 })
+        ));
+        assert_eq!(table.try_scan(2, 4).unwrap().collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+    }
+
+    #[test]
+    fn scan_wrapped() {
+        // after enough evictions the resident events straddle the end of the deque's buffer
+        let mut table = RingTable::<i32>::new(4);
+        table.append(1..=7);
+        let resident = [(4, 4), (5, 5), (6, 6), (7, 7)];
+        for start in 0..=8 {
+            for end in 0..=8 {
+                let expected = resident
+                    .iter()
+                    .copied()
+                    .filter(|(seq, _)| start < *seq && *seq <= end)
+                    .collect::<Vec<_>>();
+                assert_eq!(table.scan(start, end).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn scan_outlives_append() {
+        let mut table = RingTable::<i32>::new(2);
+        table.append([12, 34]);
+        let scan = table.scan(Seq::MIN, Seq::MAX);
+        table.append([56]);
+        assert_eq!(scan.collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(2, 34), (3, 56)]);
+    }
+
+    #[test]
+    fn set_current_seq() {
+        let mut table = RingTable::<i32>::new(2);
+        table.append([12]);
+        table.set_current_seq(10);
+        assert_eq!(table.append([34, 56]), vec![11, 12]);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(11, 34), (12, 56)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity() {
+        RingTable::<i32>::new(0);
+    }
+}
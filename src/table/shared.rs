@@ -0,0 +1,121 @@
+use std::sync::{Arc, RwLock};
+
+use crate::{Seq, Table, View};
+
+/// A handle to a table shared between threads, e.g. one writer and many readers. Cloning the handle shares the table.
+/// Scans take a read lock only long enough to clone the table, then scan the clone, so iterators never hold the lock.
+/// This suits tables whose clones share storage, like `VecTable`; for other tables, each scan copies the whole table.
+/// Other reads need `&mut` access to the table, so they take the write lock briefly instead of cloning it.
+pub struct SharedTable<T: Table + Clone> {
+    inner: Arc<RwLock<T>>,
+}
+
+impl<T: Table + Clone> SharedTable<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner: Arc::new(RwLock::new(inner)) }
+    }
+
+    /// Returns a copy of the table as of now, unaffected by later appends through any handle.
+    pub fn snapshot(&self) -> T {
+        self.inner.read().unwrap().clone()
+    }
+}
+
+impl<T: Table + Clone> Clone for SharedTable<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T: Table + Clone> View for SharedTable<T> {
+    type Event = T::Event;
+    type Iterator = T::Iterator;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        self.snapshot().scan(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.write().unwrap().get_current_seq()
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.inner
+            .write()
+            .unwrap()
+            .prefetch(start_exclusive, end_inclusive)
+    }
+
+    fn first_seq(&mut self) -> Option<Seq> {
+        self.inner.write().unwrap().first_seq()
+    }
+
+    fn seq_upper_bound(&mut self) -> Seq {
+        self.inner.write().unwrap().seq_upper_bound()
+    }
+
+    fn get_event(&mut self, seq: Seq) -> Option<(Seq, Self::Event)> {
+        self.inner.write().unwrap().get_event(seq)
+    }
+}
+
+impl<T: Table + Clone> Table for SharedTable<T> {
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq> {
+        self.inner.write().unwrap().append(events)
+    }
+
+    fn set_current_seq(&mut self, seq: Seq) {
+        self.inner.write().unwrap().set_current_seq(seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedTable;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+    use std::thread;
+
+    #[test]
+    fn concurrent_readers() {
+        let mut writer = SharedTable::new(VecTable::<u64>::new());
+        let readers = (0..4)
+            .map(|_| {
+                let mut reader = writer.clone();
+                thread::spawn(move || {
+                    let mut previous_len = 0;
+                    while previous_len < 1000 {
+                        // every scan sees a consistent prefix of the appends
+                        let events = reader.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>();
+                        assert!(events.len() >= previous_len);
+                        assert!(events.iter().all(|&(seq, event)| seq == event));
+                        previous_len = events.len();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for seq in 1..=1000 {
+            writer.append([seq]);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(writer.get_current_seq(), 1000);
+    }
+
+    #[test]
+    fn reads_through_handles() {
+        let mut writer = SharedTable::new(VecTable::<i32>::new());
+        let mut reader = writer.clone();
+        writer.append([12, 34]);
+        writer.set_current_seq(4);
+        writer.append([56]);
+
+        assert_eq!(reader.get_current_seq(), 5);
+        assert_eq!(reader.seq_upper_bound(), 5);
+        assert_eq!(reader.first_seq(), Some(1));
+        assert_eq!(reader.get_event(2), Some((2, 34)));
+        assert_eq!(reader.get_event(3), None);
+    }
+}
@@ -1,82 +1,221 @@
-use crate::{Seq, Table, View};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
 
+use crate::error::Error;
+use crate::table::range_indices;
+use crate::{Seq, SeqType, Table, TryTable, View};
+
+/// An in-memory table. Its storage is shared copy-on-write, so a scan holds a reference to it rather than a copy, and
+/// only an append while a scan is still alive copies it. Sequence numbers are `Seq` unless another `SeqType` is chosen,
+/// e.g. `VecTable::<Event, u128>::default()`.
 #[derive(Clone)]
-pub struct VecTable<Event> {
-    current_seq: Seq,
-    seqs: Vec<Seq>,
-    events: Vec<Event>,
+pub struct VecTable<Event, S = Seq> {
+    current_seq: S,
+    seqs: Arc<Vec<S>>,
+    events: Arc<Vec<Event>>,
+    // events at or before this were discarded by `truncate_before`
+    truncated_through: S,
 }
 
 impl<Event: Clone> VecTable<Event> {
     pub fn new() -> Self {
-        VecTable { seqs: Vec::new(), events: Vec::new(), current_seq: 0 }
+        Self::default()
+    }
+
+    /// Like `new`, but with room for `capacity` events before reallocating, e.g. ahead of a bulk load.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut result = Self::new();
+        result.reserve(capacity);
+        result
+    }
+}
+
+impl<Event: Clone, S: SeqType> VecTable<Event, S> {
+    /// Reserves room for at least `additional` more events, e.g. before a large `append`.
+    pub fn reserve(&mut self, additional: usize) {
+        Arc::make_mut(&mut self.seqs).reserve(additional);
+        Arc::make_mut(&mut self.events).reserve(additional);
+    }
+
+    /// Iterates over the table's events in order, borrowing them rather than cloning them as `scan` does.
+    pub fn iter(&self) -> VecTableIter<'_, Event, S> {
+        VecTableIter { seqs: &self.seqs, events: &self.events }
+    }
+
+    /// Like `scan`, but borrows the events between the given sequences rather than cloning them.
+    pub fn range(&self, start_exclusive: S, end_inclusive: S) -> VecTableIter<'_, Event, S> {
+        let (min_idx, max_idx) = range_indices(&self.seqs, start_exclusive, end_inclusive);
+        VecTableIter { seqs: &self.seqs[min_idx..max_idx], events: &self.events[min_idx..max_idx] }
+    }
+
+    /// Scan the table for events between the given sequences, in order, as contiguous slices of up to `batch` events.
+    /// The final slice may be shorter, and like `scan`, an empty range yields nothing.
+    ///
+    /// Panics if `batch` is 0.
+    pub fn scan_batched(
+        &self, start_exclusive: S, end_inclusive: S, batch: usize,
+    ) -> impl Iterator<Item = &[Event]> {
+        let (min_idx, max_idx) = range_indices(&self.seqs, start_exclusive, end_inclusive);
+        self.events[min_idx..max_idx].chunks(batch)
+    }
+
+    /// Discards the events at or before `seq`, e.g. those before a retention point. The current sequence number is
+    /// unchanged, so later appends still get higher sequence numbers, and scans including the discarded range return
+    /// the remaining events; `try_scan` returns an error for them instead.
+    pub fn truncate_before(&mut self, seq: S) {
+        self.truncated_through = self.truncated_through.max(seq.min(self.current_seq));
+        let idx = self.seqs.partition_point(|s| *s <= seq);
+        if idx == 0 {
+            return;
+        }
+        Arc::make_mut(&mut self.seqs).drain(..idx);
+        Arc::make_mut(&mut self.events).drain(..idx);
+    }
+
+    /// Like `scan`, but returns `Error::BelowRetention` if the range includes events discarded by `truncate_before`,
+    /// rather than silently returning only the remaining ones.
+    pub fn try_scan(
+        &mut self, start_exclusive: S, end_inclusive: S,
+    ) -> Result<VecTableIterator<Event, S>, Error<S>> {
+        if start_exclusive < self.truncated_through && start_exclusive < end_inclusive {
+            return Err(Error::BelowRetention {
+                seq: start_exclusive.checked_next().unwrap(),
+                first_retained: self.truncated_through.checked_next().unwrap_or(S::MAX),
+            });
+        }
+        Ok(self.scan(start_exclusive, end_inclusive))
+    }
+
+    /// Merges tables, e.g. shards of one log, into a single table ordered by sequence number. Events keep their sequence
+    /// numbers; events from different tables with equal sequence numbers are all kept, ordered by the position of their
+    /// table in `tables`, as `CompositeView` breaks ties by node id. The merged table's current sequence number is the
+    /// greatest of the inputs'.
+    pub fn merge_sorted(tables: Vec<Self>) -> Self {
+        let mut result = Self::default();
+        let seqs = Arc::make_mut(&mut result.seqs);
+        let events = Arc::make_mut(&mut result.events);
+
+        // the next unmerged event of each table, lowest (seq, table index) first
+        let mut heads = BinaryHeap::new();
+        for (table_idx, table) in tables.iter().enumerate() {
+            if let Some(&seq) = table.seqs.first() {
+                heads.push(Reverse((seq, table_idx, 0)));
+            }
+        }
+        while let Some(Reverse((seq, table_idx, idx))) = heads.pop() {
+            let table = &tables[table_idx];
+            seqs.push(seq);
+            events.push(table.events[idx].clone());
+            if let Some(&next_seq) = table.seqs.get(idx + 1) {
+                heads.push(Reverse((next_seq, table_idx, idx + 1)));
+            }
+        }
+
+        result.current_seq = tables
+            .iter()
+            .map(|table| table.current_seq)
+            .max()
+            .unwrap_or_default();
+        result
     }
 }
 
-impl<Event: Clone> Default for VecTable<Event> {
+impl<Event: Clone, S: SeqType> Default for VecTable<Event, S> {
     fn default() -> Self {
-        Self::new()
+        VecTable {
+            seqs: Default::default(),
+            events: Default::default(),
+            current_seq: S::default(),
+            truncated_through: S::default(),
+        }
     }
 }
 
-impl<Event: Clone> View for VecTable<Event> {
+impl<Event: Clone, S: SeqType> View<S> for VecTable<Event, S> {
     type Event = Event;
-    type Iterator = VecTableIterator<Event>;
+    type Iterator = VecTableIterator<Event, S>;
 
-    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
-        let reverse = start > end;
-        let (min, max) = if reverse { (end, start) } else { (start, end) };
-        VecTableIterator::new(self.clone(), reverse, min, max)
+    fn scan(&mut self, start: S, end: S) -> Self::Iterator {
+        VecTableIterator::new(self.clone(), start, end)
+    }
+
+    fn get_current_seq(&mut self) -> S {
+        self.current_seq
     }
 
-    fn get_current_seq(&mut self) -> Seq {
+    fn seq_upper_bound(&mut self) -> S {
         self.current_seq
     }
+
+    fn get_event(&mut self, seq: S) -> Option<(S, Event)> {
+        let idx = self.seqs.partition_point(|s| *s < seq);
+        (self.seqs.get(idx) == Some(&seq)).then(|| (seq, self.events[idx].clone()))
+    }
 }
 
-impl<Event: Clone> Table for VecTable<Event> {
-    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<Seq> {
+impl<Event: Clone, S: SeqType> Table<S> for VecTable<Event, S> {
+    fn append<Iter: IntoIterator<Item = Self::Event>>(&mut self, events: Iter) -> Vec<S> {
+        let seqs = Arc::make_mut(&mut self.seqs);
+        let table_events = Arc::make_mut(&mut self.events);
         let mut result = Vec::new();
         for event in events.into_iter() {
-            self.current_seq += 1;
+            self.current_seq = self
+                .current_seq
+                .checked_next()
+                .expect("sequence number overflow");
+            // scans binary search the seqs, so they must stay sorted
+            debug_assert!(
+                seqs.last().is_none_or(|last| *last < self.current_seq),
+                "sequence number {:?} follows {:?}",
+                self.current_seq,
+                seqs.last().unwrap()
+            );
             result.push(self.current_seq);
-            self.seqs.push(self.current_seq);
-            self.events.push(event);
+            seqs.push(self.current_seq);
+            table_events.push(event);
         }
         result
     }
 
-    fn set_current_seq(&mut self, seq: Seq) {
+    /// A `seq` at or below the last assigned sequence number is ignored, so later appends still follow every stored
+    /// event.
+    fn set_current_seq(&mut self, seq: S) {
         self.current_seq = self.current_seq.max(seq);
     }
 }
 
+impl<Event: Clone> TryTable for VecTable<Event> {
+    type Error = Error;
+
+    /// Like `append`, but returns an error instead of overflowing the sequence number, in which case nothing is written.
+    fn try_append<Iter: IntoIterator<Item = Self::Event>>(
+        &mut self, events: Iter,
+    ) -> Result<Vec<Seq>, Self::Error> {
+        let events = events.into_iter().collect::<Vec<_>>();
+        if self.current_seq.checked_add(events.len() as Seq).is_none() {
+            return Err(Error::SeqOverflow);
+        }
+        Ok(self.append(events))
+    }
+}
+
 #[derive(Clone)]
-pub struct VecTableIterator<Event> {
-    table: VecTable<Event>,
-    reverse: bool,
+pub struct VecTableIterator<Event, S = Seq> {
+    table: VecTable<Event, S>,
     min_idx_inclusive: usize,
     max_idx_exclusive: usize,
 }
 
-impl<Event: Clone> VecTableIterator<Event> {
-    fn new(
-        table: VecTable<Event>, reverse: bool, min_seq_exclusive: Seq, max_seq_inclusive: Seq,
-    ) -> Self {
+impl<Event: Clone, S: SeqType> VecTableIterator<Event, S> {
+    fn new(table: VecTable<Event, S>, min_seq_exclusive: S, max_seq_inclusive: S) -> Self {
         // note: we swap inclusive/exclusive because we must be able to decrement max_idx to where it excludes everything
         // if we left it inclusive, that would require usize underflow
-        let min_idx = match table.seqs.binary_search(&min_seq_exclusive) {
-            Ok(idx) => idx + 1,
-            Err(idx) => idx,
-        };
-        let max_idx = match table.seqs.binary_search(&max_seq_inclusive) {
-            Ok(idx) => idx + 1,
-            Err(idx) => idx,
-        };
-        Self { table, reverse, min_idx_inclusive: min_idx, max_idx_exclusive: max_idx }
+        let (min_idx, max_idx) = range_indices(&table.seqs, min_seq_exclusive, max_seq_inclusive);
+        Self { table, min_idx_inclusive: min_idx, max_idx_exclusive: max_idx }
     }
 
-    fn next(&mut self) -> Option<(Seq, Event)> {
+    fn next(&mut self) -> Option<(S, Event)> {
         if self.min_idx_inclusive == self.max_idx_exclusive {
             None
         } else {
@@ -87,7 +226,7 @@ impl<Event: Clone> VecTableIterator<Event> {
         }
     }
 
-    fn next_back(&mut self) -> Option<(Seq, Event)> {
+    fn next_back(&mut self) -> Option<(S, Event)> {
         if self.min_idx_inclusive == self.max_idx_exclusive {
             None
         } else {
@@ -99,24 +238,335 @@ impl<Event: Clone> VecTableIterator<Event> {
     }
 }
 
-impl<Event: Clone> Iterator for VecTableIterator<Event> {
-    type Item = (Seq, Event);
+impl<Event: Clone, S: SeqType> Iterator for VecTableIterator<Event, S> {
+    type Item = (S, Event);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if !self.reverse {
-            VecTableIterator::<Event>::next(self)
-        } else {
-            VecTableIterator::<Event>::next_back(self)
-        }
+        VecTableIterator::<Event, S>::next(self)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.max_idx_exclusive - self.min_idx_inclusive;
+        (len, Some(len))
     }
 }
 
-impl<Event: Clone> DoubleEndedIterator for VecTableIterator<Event> {
+// both directions consume the same index range, so its length is what remains either way
+impl<Event: Clone, S: SeqType> ExactSizeIterator for VecTableIterator<Event, S> {}
+
+impl<Event: Clone, S: SeqType> DoubleEndedIterator for VecTableIterator<Event, S> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if !self.reverse {
-            VecTableIterator::<Event>::next_back(self)
-        } else {
-            VecTableIterator::<Event>::next(self)
+        VecTableIterator::<Event, S>::next_back(self)
+    }
+}
+
+/// Borrows a range of a `VecTable`'s events, so unlike `VecTableIterator` it never clones them.
+pub struct VecTableIter<'a, Event, S = Seq> {
+    seqs: &'a [S],
+    events: &'a [Event],
+}
+
+impl<Event, S> Clone for VecTableIter<'_, Event, S> {
+    fn clone(&self) -> Self {
+        Self { seqs: self.seqs, events: self.events }
+    }
+}
+
+impl<'a, Event, S: SeqType> Iterator for VecTableIter<'a, Event, S> {
+    type Item = (S, &'a Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (seq, seqs) = self.seqs.split_first()?;
+        let (event, events) = self.events.split_first()?;
+        (self.seqs, self.events) = (seqs, events);
+        Some((*seq, event))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.seqs.len(), Some(self.seqs.len()))
+    }
+}
+
+impl<Event, S: SeqType> ExactSizeIterator for VecTableIter<'_, Event, S> {}
+
+impl<Event, S: SeqType> DoubleEndedIterator for VecTableIter<'_, Event, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (seq, seqs) = self.seqs.split_last()?;
+        let (event, events) = self.events.split_last()?;
+        (self.seqs, self.events) = (seqs, events);
+        Some((*seq, event))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::sync::Arc;
+
+    use serde::de::{DeserializeOwned, Error as _};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::VecTable;
+    use crate::Seq;
+
+    #[derive(Serialize)]
+    struct VecTableRef<'a, Event> {
+        current_seq: Seq,
+        seqs: &'a [Seq],
+        events: &'a [Event],
+        truncated_through: Seq,
+    }
+
+    #[derive(Deserialize)]
+    struct VecTableData<Event> {
+        current_seq: Seq,
+        seqs: Vec<Seq>,
+        events: Vec<Event>,
+        // absent from tables serialized before it was added
+        #[serde(default)]
+        truncated_through: Seq,
+    }
+
+    impl<Event: Serialize> Serialize for VecTable<Event> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            VecTableRef {
+                current_seq: self.current_seq,
+                seqs: &self.seqs,
+                events: &self.events,
+                truncated_through: self.truncated_through,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    /// Rejects data that would break scans, e.g. from a corrupted file: sequence numbers must be ascending, at most the
+    /// current sequence number, and one per event.
+    impl<'de, Event: DeserializeOwned> Deserialize<'de> for VecTable<Event> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let data = VecTableData::<Event>::deserialize(deserializer)?;
+            if data.seqs.len() != data.events.len() {
+                return Err(D::Error::custom(format!(
+                    "{} sequence numbers for {} events",
+                    data.seqs.len(),
+                    data.events.len()
+                )));
+            }
+            if let Some(pair) = data.seqs.windows(2).find(|pair| pair[0] > pair[1]) {
+                return Err(D::Error::custom(format!(
+                    "sequence number {} follows {}",
+                    pair[1], pair[0]
+                )));
+            }
+            if let Some(&last) = data.seqs.last() {
+                if last > data.current_seq {
+                    return Err(D::Error::custom(format!(
+                        "sequence number {} is past the current sequence number {}",
+                        last, data.current_seq
+                    )));
+                }
+            }
+            Ok(VecTable {
+                current_seq: data.current_seq,
+                seqs: Arc::new(data.seqs),
+                events: Arc::new(data.events),
+                truncated_through: data.truncated_through,
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::table::vec::VecTable;
+        use crate::{Seq, Table, View};
+
+        fn table() -> VecTable<String> {
+            let mut table = VecTable::new();
+            table.append(["a".to_string(), "b".to_string()]);
+            table.set_current_seq(5);
+            table.append(["c".to_string()]);
+            table.set_current_seq(8);
+            table
+        }
+
+        #[test]
+        fn json() {
+            let mut table = table();
+            let mut reloaded: VecTable<String> =
+                serde_json::from_str(&serde_json::to_string(&table).unwrap()).unwrap();
+            assert_eq!(reloaded.get_current_seq(), 8);
+            assert_eq!(
+                reloaded.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+                table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn bincode() {
+            let mut table = table();
+            let mut reloaded: VecTable<String> =
+                bincode::deserialize(&bincode::serialize(&table).unwrap()).unwrap();
+            assert_eq!(reloaded.get_current_seq(), 8);
+            assert_eq!(
+                reloaded.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+                table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn invalid() {
+            let unsorted = r#"{"current_seq":3,"seqs":[2,1],"events":["a","b"]}"#;
+            let err = serde_json::from_str::<VecTable<String>>(unsorted)
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("sequence number 1 follows 2"));
+
+            let past_current = r#"{"current_seq":1,"seqs":[1,2],"events":["a","b"]}"#;
+            let err = serde_json::from_str::<VecTable<String>>(past_current)
+                .err()
+                .unwrap();
+            assert!(err
+                .to_string()
+                .contains("past the current sequence number 1"));
+
+            let mismatched = r#"{"current_seq":3,"seqs":[1],"events":["a","b"]}"#;
+            let err = serde_json::from_str::<VecTable<String>>(mismatched)
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("1 sequence numbers for 2 events"));
+        }
+    }
+}
+
+#[cfg(feature = "bincode")]
+mod bincode_impl {
+    use std::io::{self, Read, Write};
+
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use super::VecTable;
+
+    /// Identifies a saved `VecTable`.
+    const MAGIC: &[u8; 4] = b"PDVT";
+
+    /// The version of the saved format, incremented when it changes incompatibly.
+    const VERSION: u32 = 1;
+
+    fn into_io(err: bincode::ErrorKind) -> io::Error {
+        match err {
+            bincode::ErrorKind::Io(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+
+    impl<Event: Clone + Serialize + DeserializeOwned> VecTable<Event> {
+        /// Writes the table's full contents: a header of magic bytes, format version, and event count, followed by the
+        /// table encoded with bincode.
+        pub fn save(&self, mut w: impl Write) -> io::Result<()> {
+            w.write_all(MAGIC)?;
+            w.write_all(&VERSION.to_le_bytes())?;
+            w.write_all(&(self.events.len() as u64).to_le_bytes())?;
+            bincode::serialize_into(w, self).map_err(|err| into_io(*err))
+        }
+
+        /// Reads a table written by `save`. Returns an `InvalidData` error if the header doesn't match or the contents
+        /// are invalid, e.g. out-of-order sequence numbers, and an `UnexpectedEof` error if the input is truncated.
+        pub fn load(mut r: impl Read) -> io::Result<Self> {
+            let mut magic = [0; 4];
+            r.read_exact(&mut magic)?;
+            if &magic != MAGIC {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "not a saved table"));
+            }
+            let mut version = [0; 4];
+            r.read_exact(&mut version)?;
+            let version = u32::from_le_bytes(version);
+            if version != VERSION {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unsupported table format version {}", version),
+                ));
+            }
+            let mut len = [0; 8];
+            r.read_exact(&mut len)?;
+            let len = u64::from_le_bytes(len);
+
+            let table: Self = bincode::deserialize_from(r).map_err(|err| into_io(*err))?;
+            if table.events.len() as u64 != len {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("header declares {} events but {} were read", len, table.events.len()),
+                ));
+            }
+            Ok(table)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::io;
+
+        use crate::table::vec::VecTable;
+        use crate::{Seq, Table, View};
+
+        fn saved() -> (VecTable<String>, Vec<u8>) {
+            let mut table = VecTable::new();
+            table.append(["a".to_string(), "b".to_string()]);
+            table.set_current_seq(5);
+            table.append(["c".to_string()]);
+            let mut bytes = Vec::new();
+            table.save(&mut bytes).unwrap();
+            (table, bytes)
+        }
+
+        #[test]
+        fn round_trip() {
+            let (mut table, bytes) = saved();
+            let mut loaded = VecTable::<String>::load(bytes.as_slice()).unwrap();
+            assert_eq!(loaded.get_current_seq(), 6);
+            assert_eq!(
+                loaded.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+                table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn truncated() {
+            let (_, bytes) = saved();
+            for len in [0, 3, 10, bytes.len() - 1] {
+                let err = VecTable::<String>::load(&bytes[..len]).err().unwrap();
+                assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof, "length {}", len);
+            }
+        }
+
+        #[test]
+        fn corrupted() {
+            let (_, bytes) = saved();
+
+            let mut bad_magic = bytes.clone();
+            bad_magic[0] = b'X';
+            let err = VecTable::<String>::load(bad_magic.as_slice())
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), "not a saved table");
+
+            let mut bad_version = bytes.clone();
+            bad_version[4] = 2;
+            let err = VecTable::<String>::load(bad_version.as_slice())
+                .err()
+                .unwrap();
+            assert_eq!(err.to_string(), "unsupported table format version 2");
+
+            let mut bad_len = bytes.clone();
+            bad_len[8] = 2;
+            let err = VecTable::<String>::load(bad_len.as_slice()).err().unwrap();
+            assert_eq!(err.to_string(), "header declares 2 events but 3 were read");
+
+            // the first of the seqs follows the 8-byte current seq and 8-byte seq count
+            let mut unsorted = bytes.clone();
+            unsorted[16 + 16] = 9;
+            let err = VecTable::<String>::load(unsorted.as_slice()).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains("sequence number 2 follows 9"));
         }
     }
 }
@@ -124,7 +574,11 @@ impl<Event: Clone> DoubleEndedIterator for VecTableIterator<Event> {
 #[cfg(test)]
 mod tests {
     use super::VecTable;
-    use crate::{Seq, Table, View};
+    use crate::error::Error;
+    use crate::{Seq, Table, TryTable, View};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn scan_none() {
@@ -139,6 +593,323 @@ mod tests {
         );
     }
 
+    #[test]
+    fn empty() {
+        let mut table = VecTable::<i32>::new();
+        assert_eq!(table.get_current_seq(), 0);
+        assert_eq!(table.first_seq(), None);
+        assert_eq!(table.scan(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn scan_beyond_current_seq() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56]);
+        assert_eq!(table.get_current_seq(), 3);
+        assert_eq!(table.scan(1, Seq::MAX - 1).collect::<Vec<_>>(), vec![(2, 34), (3, 56)]);
+        assert_eq!(
+            table.scan(1, Seq::MAX - 1).collect::<Vec<_>>(),
+            table.scan(1, 3).collect::<Vec<_>>()
+        );
+        assert_eq!(table.scan(3, 1000 * 3).count(), 0);
+    }
+
+    #[test]
+    fn try_append_overflow() {
+        let mut table = VecTable::<i32>::new();
+        table.set_current_seq(Seq::MAX - 1);
+        assert!(matches!(table.try_append([12, 34]), Err(Error::SeqOverflow)));
+        assert_eq!(table.get_current_seq(), Seq::MAX - 1);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).count(), 0);
+        assert_eq!(table.try_append([12]).unwrap(), vec![Seq::MAX]);
+    }
+
+    #[test]
+    fn append_none() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12]);
+        assert_eq!(table.append([]), Vec::<Seq>::new());
+        assert_eq!(table.get_current_seq(), 1);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12)]);
+    }
+
+    #[test]
+    fn append_ordered() {
+        let mut table = VecTable::<i32>::new();
+        assert_eq!(table.append_ordered(vec![12, 34, 56, 78]), vec![1, 2, 3, 4]);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (2, 34), (3, 56), (4, 78)]
+        );
+    }
+
+    #[test]
+    fn u128_seqs() {
+        let mut table = VecTable::<i32, u128>::default();
+        table.set_current_seq(u64::MAX as u128);
+        assert_eq!(table.append([12, 34]), vec![u64::MAX as u128 + 1, u64::MAX as u128 + 2]);
+        assert_eq!(table.get_current_seq(), u64::MAX as u128 + 2);
+        assert_eq!(table.first_seq(), Some(u64::MAX as u128 + 1));
+        assert_eq!(
+            table
+                .scan_rev(u64::MAX as u128, u128::MAX)
+                .collect::<Vec<_>>(),
+            vec![(u64::MAX as u128 + 2, 34), (u64::MAX as u128 + 1, 12)]
+        );
+    }
+
+    #[test]
+    fn scan_len() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56, 78]);
+        assert_eq!(table.scan(1, 3).len(), 2);
+
+        let mut iter = table.scan(0, 4);
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.next_back();
+        assert_eq!(iter.len(), 2);
+
+        let mut iter = table.scan(0, 4).rev();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+
+        let mut iter = table.scan_rev(1, 4);
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(table.scan(3, 3).len(), 0);
+        assert_eq!(table.scan(4, 1).len(), 0);
+    }
+
+    #[test]
+    fn get_event() {
+        let mut table = VecTable::<i32>::new();
+        assert_eq!(table.get_event(1), None);
+
+        table.append([12, 34]);
+        table.set_current_seq(5);
+        table.append([56]);
+        assert_eq!(table.get_event(1), Some((1, 12)));
+        assert_eq!(table.get_event(2), Some((2, 34)));
+        assert_eq!(table.get_event(6), Some((6, 56)));
+
+        // skipped, unassigned, and boundary seqs
+        assert_eq!(table.get_event(4), None);
+        assert_eq!(table.get_event(7), None);
+        assert_eq!(table.get_event(Seq::MIN), None);
+        assert_eq!(table.get_event(Seq::MAX), None);
+    }
+
+    #[test]
+    fn set_current_seq_backwards() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56]);
+        table.set_current_seq(1);
+        assert_eq!(table.get_current_seq(), 3);
+        assert_eq!(table.append([78]), vec![4]);
+        assert_eq!(
+            table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (2, 34), (3, 56), (4, 78)]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "sequence number 2 follows 5")]
+    fn append_out_of_order() {
+        // only reachable by breaking the table's invariant from inside the module
+        let mut table: VecTable<i32> = VecTable {
+            current_seq: 1,
+            seqs: Arc::new(vec![5]),
+            events: Arc::new(vec![12]),
+            ..VecTable::default()
+        };
+        table.append([34]);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut table = VecTable::<i32>::with_capacity(100);
+        assert!(table.seqs.capacity() >= 100);
+        assert!(table.events.capacity() >= 100);
+        assert_eq!(table.get_current_seq(), 0);
+
+        table.append([12, 34]);
+        table.reserve(1000);
+        assert!(table.seqs.capacity() >= 1002);
+        assert!(table.events.capacity() >= 1002);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+    }
+
+    #[test]
+    fn scan_rev() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56]);
+        assert_eq!(
+            table.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(3, 56), (2, 34), (1, 12)]
+        );
+        assert_eq!(table.scan_rev(1, 2).collect::<Vec<_>>(), vec![(2, 34)]);
+
+        // like scan, an inverted range is empty rather than reversed
+        assert_eq!(table.scan(2, 1).count(), 0);
+        assert_eq!(table.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(table.scan_rev(2, 1).count(), 0);
+        assert_eq!(table.scan_rev(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(table.scan_rev(2, 2).count(), 0);
+    }
+
+    #[test]
+    fn iter() {
+        let mut table = VecTable::<i32>::new();
+        assert_eq!(table.iter().count(), 0);
+
+        table.append([12, 34]);
+        table.set_current_seq(4);
+        table.append([56]);
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec![(1, &12), (2, &34), (5, &56)]);
+        assert_eq!(table.iter().next_back(), Some((5, &56)));
+        assert_eq!(table.iter().len(), 3);
+
+        // ranges are found as scans find them
+        assert_eq!(table.range(1, 5).collect::<Vec<_>>(), vec![(2, &34), (5, &56)]);
+        assert_eq!(table.range(0, 4).rev().collect::<Vec<_>>(), vec![(2, &34), (1, &12)]);
+        assert_eq!(table.range(2, 4).len(), 0);
+        assert_eq!(table.range(5, 1).count(), 0);
+    }
+
+    /// An event that counts how many times it's been cloned.
+    struct CloneCounter(Rc<Cell<usize>>);
+
+    impl Clone for CloneCounter {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            Self(Rc::clone(&self.0))
+        }
+    }
+
+    #[test]
+    fn scan_shares_storage() {
+        let clones = Rc::new(Cell::new(0));
+        let mut table = VecTable::new();
+        table.append([CloneCounter(Rc::clone(&clones)), CloneCounter(Rc::clone(&clones))]);
+
+        // neither scanning nor iterating copies the events
+        let scan = table.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(table.iter().count(), 2);
+        assert_eq!(clones.get(), 0);
+
+        // appending while a scan is alive copies them, leaving the scan unaffected
+        table.append([CloneCounter(Rc::clone(&clones))]);
+        assert_eq!(clones.get(), 2);
+        assert_eq!(scan.map(|(seq, _)| seq).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(clones.get(), 4);
+
+        // appending once no scan is alive doesn't
+        table.append([CloneCounter(Rc::clone(&clones))]);
+        assert_eq!(clones.get(), 4);
+        assert_eq!(table.iter().map(|(seq, _)| seq).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn truncate_before() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34]);
+        table.set_current_seq(4);
+        table.append([56, 78]);
+
+        table.truncate_before(0);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).count(), 4);
+
+        table.truncate_before(3);
+        assert_eq!(table.get_current_seq(), 6);
+        assert_eq!(table.first_seq(), Some(5));
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(5, 56), (6, 78)]);
+        assert_eq!(table.scan(1, 5).collect::<Vec<_>>(), vec![(5, 56)]);
+        assert_eq!(table.scan_rev(0, 6).collect::<Vec<_>>(), vec![(6, 78), (5, 56)]);
+        assert_eq!(table.scan(1, 4).count(), 0);
+
+        // only a range including discarded events is an error
+        assert!(matches!(
+            table.try_scan(2, 5),
+            Err(Error::BelowRetention { seq: 3, first_retained: 4 })
+        ));
+        assert_eq!(table.try_scan(3, 5).unwrap().collect::<Vec<_>>(), vec![(5, 56)]);
+        assert_eq!(table.try_scan(2, 2).unwrap().count(), 0);
+
+        table.truncate_before(Seq::MAX);
+        assert_eq!(table.first_seq(), None);
+        assert_eq!(table.append([90]), vec![7]);
+        assert_eq!(table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(7, 90)]);
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut a = VecTable::<&str>::new();
+        let mut b = VecTable::<&str>::new();
+        let mut c = VecTable::<&str>::new();
+        a.append(["a1"]);
+        a.set_current_seq(4);
+        a.append(["a5", "a6"]);
+        b.set_current_seq(1);
+        b.append(["b2"]);
+        b.set_current_seq(5);
+        b.append(["b6"]);
+        c.set_current_seq(2);
+        c.append(["c3", "c4"]);
+        c.set_current_seq(9);
+
+        let mut merged = VecTable::merge_sorted(vec![a, b, c]);
+        assert_eq!(merged.get_current_seq(), 9);
+        assert_eq!(
+            merged.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, "a1"), (2, "b2"), (3, "c3"), (4, "c4"), (5, "a5"), (6, "a6"), (6, "b6")]
+        );
+        assert_eq!(merged.scan(5, 6).collect::<Vec<_>>(), vec![(6, "a6"), (6, "b6")]);
+        assert_eq!(
+            merged.scan_rev(4, 6).collect::<Vec<_>>(),
+            vec![(6, "b6"), (6, "a6"), (5, "a5")]
+        );
+        assert_eq!(merged.scan(6, Seq::MAX).count(), 0);
+
+        assert_eq!(VecTable::<&str>::merge_sorted(vec![]).get_current_seq(), 0);
+    }
+
+    #[test]
+    fn scan_batched() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56, 78, 90]);
+
+        let batches = table
+            .scan_batched(Seq::MIN, Seq::MAX, 2)
+            .collect::<Vec<_>>();
+        assert_eq!(batches, vec![&[12, 34][..], &[56, 78][..], &[90][..]]);
+        assert_eq!(
+            batches.concat(),
+            table
+                .scan(Seq::MIN, Seq::MAX)
+                .map(|(_, event)| event)
+                .collect::<Vec<i32>>()
+        );
+
+        assert_eq!(table.scan_batched(1, 4, 2).collect::<Vec<_>>(), vec![&[34, 56][..], &[78][..]]);
+        assert_eq!(table.scan_batched(1, 4, 3).collect::<Vec<_>>(), vec![&[34, 56, 78][..]]);
+        assert_eq!(table.scan_batched(5, Seq::MAX, 2).count(), 0);
+        assert_eq!(table.scan_batched(4, 1, 2).count(), 0);
+    }
+
+    #[test]
+    fn scan_after_prefetch() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56, 78]);
+        let expected = table.scan(1, 3).collect::<Vec<_>>();
+        table.prefetch(1, 3);
+        assert_eq!(table.scan(1, 3).collect::<Vec<_>>(), expected);
+    }
+
     #[test]
     fn scan_one() {
         let mut table = VecTable::<i32>::new();
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use crate::{Seq, View};
+
+/// Asserts that two map-valued indexes over the same source agree at each of the given seqs, e.g. to validate a custom
+/// index against a naive reference. Each index is read through a closure such as `|source, seq| index.get_all(source,
+/// seq)`, so indexes of different types can be compared.
+pub fn assert_indexes_agree<Source, Key, Value>(
+    source: &mut Source, a: impl Fn(&mut Source, Seq) -> HashMap<Key, Value>,
+    b: impl Fn(&mut Source, Seq) -> HashMap<Key, Value>, seqs: &[Seq],
+) where
+    Source: View,
+    Key: Debug + Eq + Hash,
+    Value: Debug + PartialEq,
+{
+    for &seq in seqs {
+        assert_eq!(a(source, seq), b(source, seq), "indexes disagree at seq {}", seq);
+    }
+}
+
+/// Test fixtures shared across modules.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use crate::table::vec::{VecTable, VecTableIterator};
+    use crate::{Seq, View};
+
+    /// What a `CountingView` has been asked for. Views can share one, e.g. to count across the nodes of a composite.
+    #[derive(Default)]
+    pub struct Counts {
+        pub scans: Cell<usize>,
+        /// Pulls from scans' iterators, including pulls that find them exhausted.
+        pub pulls: Cell<usize>,
+        /// Events those pulls yielded.
+        pub events: Cell<usize>,
+        pub prefetches: RefCell<Vec<(Seq, Seq)>>,
+    }
+
+    impl Counts {
+        pub fn reset(&self) {
+            self.scans.set(0);
+            self.pulls.set(0);
+            self.events.set(0);
+            self.prefetches.borrow_mut().clear();
+        }
+    }
+
+    /// A `VecTable` that counts how it's read.
+    pub struct CountingView<Event> {
+        pub table: VecTable<Event>,
+        pub counts: Rc<Counts>,
+    }
+
+    impl<Event: Clone> CountingView<Event> {
+        pub fn new(counts: Rc<Counts>) -> Self {
+            Self { table: VecTable::new(), counts }
+        }
+    }
+
+    pub struct CountingIterator<Event> {
+        inner: VecTableIterator<Event>,
+        counts: Rc<Counts>,
+    }
+
+    impl<Event> CountingIterator<Event> {
+        fn record(&self, result: &Option<(Seq, Event)>) {
+            self.counts.pulls.set(self.counts.pulls.get() + 1);
+            self.counts
+                .events
+                .set(self.counts.events.get() + result.iter().count());
+        }
+    }
+
+    impl<Event: Clone> View for CountingView<Event> {
+        type Event = Event;
+        type Iterator = CountingIterator<Event>;
+
+        fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+            self.counts.scans.set(self.counts.scans.get() + 1);
+            CountingIterator {
+                inner: self.table.scan(start_exclusive, end_inclusive),
+                counts: self.counts.clone(),
+            }
+        }
+
+        fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+            self.counts
+                .prefetches
+                .borrow_mut()
+                .push((start_exclusive, end_inclusive));
+        }
+
+        fn get_current_seq(&mut self) -> Seq {
+            self.table.get_current_seq()
+        }
+
+        fn seq_upper_bound(&mut self) -> Seq {
+            self.table.seq_upper_bound()
+        }
+    }
+
+    impl<Event: Clone> Iterator for CountingIterator<Event> {
+        type Item = (Seq, Event);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let result = self.inner.next();
+            self.record(&result);
+            result
+        }
+    }
+
+    impl<Event: Clone> DoubleEndedIterator for CountingIterator<Event> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            let result = self.inner.next_back();
+            self.record(&result);
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assert_indexes_agree;
+    use crate::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+    use crate::table::vec::VecTable;
+    use crate::{Index, Seq, Table, View};
+    use std::collections::HashMap;
+
+    type Update = HashMapUpdate<u64, u64>;
+
+    // replays every event from the beginning
+    fn naive_get_all(source: &mut VecTable<Update>, seq: Seq) -> HashMap<u64, u64> {
+        let mut result = HashMap::new();
+        for (_, update) in source.scan(0, seq) {
+            match update {
+                HashMapUpdate::Insert { key, value } => {
+                    result.insert(key, value);
+                }
+                HashMapUpdate::Remove { key } => {
+                    result.remove(&key);
+                }
+                HashMapUpdate::Clear => {
+                    result.clear();
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn hash_map_index_agrees_with_naive() {
+        let mut table = VecTable::<Update>::new();
+        table.append((0..100).map(|i| match i % 7 {
+            3 => HashMapUpdate::Remove { key: i % 5 },
+            _ if i % 23 == 0 => HashMapUpdate::Clear,
+            _ => HashMapUpdate::Insert { key: i % 5, value: i },
+        }));
+        let seqs = (0..=100).collect::<Vec<_>>();
+
+        for current_seq in [0, 1, 50, 100] {
+            let mut index = HashMapIndex::new(|update: Update| vec![update]);
+            index.update(&mut table, current_seq);
+            assert_indexes_agree(
+                &mut table,
+                |source, seq| index.get_all(source, seq),
+                naive_get_all,
+                &seqs,
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "indexes disagree at seq 1")]
+    fn disagree() {
+        let mut table = VecTable::<Update>::new();
+        table.append([HashMapUpdate::Insert { key: 1, value: 1 }]);
+        assert_indexes_agree(&mut table, naive_get_all, |_, _| HashMap::new(), &[0, 1]);
+    }
+}
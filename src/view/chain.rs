@@ -0,0 +1,217 @@
+use crate::{Seq, View};
+
+/// A range of one view in a chain, in that view's own sequence numbers, or `None` if the view has no part in a scan.
+type PartRange = Option<(Seq, Seq)>;
+
+/// Concatenates two views, e.g. an archived cold log and the hot log that continues it. `cold`'s events keep their
+/// sequence numbers, up to its current sequence number, and `hot`'s follow, offset by `cold`'s current sequence number,
+/// so the chain's current sequence number is the sum of theirs. `cold` is expected not to grow while it's chained.
+pub struct ChainView<A: View, B: View<Event = A::Event>> {
+    cold: A,
+    hot: B,
+}
+
+impl<A: View, B: View<Event = A::Event>> ChainView<A, B> {
+    pub fn new(cold: A, hot: B) -> Self {
+        Self { cold, hot }
+    }
+
+    pub fn cold_mut(&mut self) -> &mut A {
+        &mut self.cold
+    }
+
+    pub fn hot_mut(&mut self) -> &mut B {
+        &mut self.hot
+    }
+
+    /// Returns the offset of `hot`'s sequence numbers and the ranges of `cold` and `hot` that make up the given range of
+    /// the chain.
+    fn split(&mut self, start: Seq, end: Seq) -> (Seq, PartRange, PartRange) {
+        let boundary = self.cold.get_current_seq();
+        let cold = (start < boundary && start < end).then(|| (start, end.min(boundary)));
+        let hot = (end > boundary && start < end)
+            .then(|| (start.max(boundary) - boundary, end - boundary));
+        (boundary, cold, hot)
+    }
+}
+
+impl<A: View, B: View<Event = A::Event>> View for ChainView<A, B> {
+    type Event = A::Event;
+    type Iterator = ChainViewIterator<A, B>;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        let (offset, cold, hot) = self.split(start, end);
+        ChainViewIterator {
+            cold: cold.map(|(start, end)| self.cold.scan(start, end)),
+            hot: hot.map(|(start, end)| self.hot.scan(start, end)),
+            offset,
+        }
+    }
+
+    fn prefetch(&mut self, start: Seq, end: Seq) {
+        let (_, cold, hot) = self.split(start, end);
+        if let Some((start, end)) = cold {
+            self.cold.prefetch(start, end);
+        }
+        if let Some((start, end)) = hot {
+            self.hot.prefetch(start, end);
+        }
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.cold
+            .get_current_seq()
+            .checked_add(self.hot.get_current_seq())
+            .expect("sequence number overflow")
+    }
+}
+
+pub struct ChainViewIterator<A: View, B: View<Event = A::Event>> {
+    cold: Option<A::Iterator>,
+    hot: Option<B::Iterator>,
+    // the cold view's current seq at the time of the scan, which the hot view's seqs are offset by
+    offset: Seq,
+}
+
+impl<A: View, B: View<Event = A::Event>> ChainViewIterator<A, B> {
+    fn next_cold(&mut self, front: bool) -> Option<(Seq, A::Event)> {
+        let cold = self.cold.as_mut()?;
+        if front {
+            cold.next()
+        } else {
+            cold.next_back()
+        }
+    }
+
+    fn next_hot(&mut self, front: bool) -> Option<(Seq, A::Event)> {
+        let hot = self.hot.as_mut()?;
+        let (seq, event) = if front { hot.next() } else { hot.next_back() }?;
+        let seq = seq
+            .checked_add(self.offset)
+            .expect("sequence number overflow");
+        Some((seq, event))
+    }
+
+    /// Takes the next event from the front or back, exhausting the half of the chain at that end first.
+    fn take(&mut self, front: bool) -> Option<(Seq, A::Event)> {
+        if front {
+            self.next_cold(front).or_else(|| self.next_hot(front))
+        } else {
+            self.next_hot(front).or_else(|| self.next_cold(front))
+        }
+    }
+}
+
+impl<A: View, B: View<Event = A::Event>> Iterator for ChainViewIterator<A, B> {
+    type Item = (Seq, A::Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.take(true)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let hint = |iter: Option<(usize, Option<usize>)>| iter.unwrap_or((0, Some(0)));
+        let (cold_min, cold_max) = hint(self.cold.as_ref().map(Iterator::size_hint));
+        let (hot_min, hot_max) = hint(self.hot.as_ref().map(Iterator::size_hint));
+        let max = match (cold_max, hot_max) {
+            (Some(cold_max), Some(hot_max)) => cold_max.checked_add(hot_max),
+            _ => None,
+        };
+        (cold_min.saturating_add(hot_min), max)
+    }
+}
+
+impl<A: View, B: View<Event = A::Event>> DoubleEndedIterator for ChainViewIterator<A, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.take(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChainView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    fn chain() -> ChainView<VecTable<i32>, VecTable<i32>> {
+        let mut view = ChainView::new(VecTable::new(), VecTable::new());
+        view.cold_mut().append([12, 34, 56]);
+        view.hot_mut().append([78, 90]);
+        view
+    }
+
+    #[test]
+    fn scan() {
+        let mut view = chain();
+        assert_eq!(view.get_current_seq(), 5);
+
+        let all = vec![(1, 12), (2, 34), (3, 56), (4, 78), (5, 90)];
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), all);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(),
+            all.iter().copied().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+    }
+
+    #[test]
+    fn scan_cold() {
+        let mut view = chain();
+        assert_eq!(view.scan(0, 2).collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+        assert_eq!(view.scan(1, 3).collect::<Vec<_>>(), vec![(2, 34), (3, 56)]);
+        assert_eq!(view.scan_rev(1, 3).collect::<Vec<_>>(), vec![(3, 56), (2, 34)]);
+    }
+
+    #[test]
+    fn scan_hot() {
+        let mut view = chain();
+        assert_eq!(view.scan(3, 5).collect::<Vec<_>>(), vec![(4, 78), (5, 90)]);
+        assert_eq!(view.scan(4, Seq::MAX).collect::<Vec<_>>(), vec![(5, 90)]);
+        assert_eq!(view.scan_rev(3, 5).collect::<Vec<_>>(), vec![(5, 90), (4, 78)]);
+        assert_eq!(view.scan(5, Seq::MAX).count(), 0);
+    }
+
+    #[test]
+    fn scan_straddling() {
+        let mut view = chain();
+        assert_eq!(view.scan(2, 4).collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+        assert_eq!(view.scan_rev(2, 4).collect::<Vec<_>>(), vec![(4, 78), (3, 56)]);
+        assert_eq!(view.scan(4, 2).count(), 0);
+
+        let mut iter = view.scan(1, 5);
+        assert_eq!(iter.next_back(), Some((5, 90)));
+        assert_eq!(iter.next(), Some((2, 34)));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+    }
+
+    #[test]
+    fn size_hint() {
+        let mut view = chain();
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).size_hint(), (5, Some(5)));
+        assert_eq!(view.scan(1, 4).size_hint(), (3, Some(3)));
+        assert_eq!(view.scan(3, 5).size_hint(), (2, Some(2)));
+        assert_eq!(view.scan(4, 2).size_hint(), (0, Some(0)));
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "sequence number overflow")]
+    fn current_seq_overflow() {
+        let mut view = chain();
+        view.cold_mut().set_current_seq(Seq::MAX - 1);
+        view.get_current_seq();
+    }
+
+    #[test]
+    fn empty_halves() {
+        let mut view = ChainView::new(VecTable::<i32>::new(), VecTable::new());
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).count(), 0);
+
+        view.hot_mut().append([12]);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12)]);
+    }
+}
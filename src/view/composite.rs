@@ -1,132 +1,337 @@
-use crate::{Seq, View};
+use crate::error::Error;
+use crate::{Seq, SeqType, View};
+
+/// How a `CompositeView` orders events from different nodes with the same sequence number.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Events are ordered by (seq, node id).
+    #[default]
+    LowNodeFirst,
+    /// Events are ordered by seq, then by node id descending.
+    HighNodeFirst,
+}
 
+/// Merges constituent views, one per node, ordering events by (seq, node). Constituent views are expected not to expose
+/// events beyond their current sequence numbers, as is the case for tables.
+///
+/// A node's id is its view's index in `new`, or the id returned by `add_view`, and stays the same when other views are
+/// removed. A composite of zero views is valid and empty: scans yield nothing, its current sequence number is 0, and it
+/// has no frontier or slowest node.
 #[derive(Clone)]
-pub struct CompositeView<V: View> {
+pub struct CompositeView<V: View<S>, S: SeqType = Seq> {
     views: Vec<V>,
-    vector_clock: Vec<Seq>,
+    // the node id of each view, ascending. Removed nodes' ids are dropped rather than reused, so ids, and so vector clock
+    // entries, stay stable
+    node_ids: Vec<usize>,
+    // indexed by node id, including removed nodes
+    vector_clock: Vec<S>,
+    tie_break: TieBreak,
 }
 
-impl<V: View> CompositeView<V> {
+impl<V: View<S>, S: SeqType> CompositeView<V, S> {
     pub fn new(views: Vec<V>) -> Self {
-        let vector_clock = vec![0; views.len()];
-        Self { views, vector_clock }
+        Self::with_tie_break(views, TieBreak::default())
+    }
+
+    /// Like `new`, but ordering events with the same sequence number by `tie_break`. Reversed iterators, e.g. from
+    /// `scan_rev`, yield exactly the reverse of this order.
+    pub fn with_tie_break(views: Vec<V>, tie_break: TieBreak) -> Self {
+        let vector_clock = vec![S::default(); views.len()];
+        let node_ids = (0..views.len()).collect();
+        Self { views, node_ids, vector_clock, tie_break }
+    }
+
+    /// Adds a view for a node joining the composite and returns its node id. Its vector clock entry starts at 0, holding
+    /// back the composite's current sequence number until the node promises otherwise.
+    pub fn add_view(&mut self, view: V) -> usize {
+        let node_id = self.vector_clock.len();
+        self.views.push(view);
+        self.node_ids.push(node_id);
+        self.vector_clock.push(S::default());
+        node_id
+    }
+
+    /// Removes the view of a node leaving the composite and returns it, or `None` if there's no such node. Its events no
+    /// longer appear in scans and its vector clock entry no longer holds back the composite's current sequence number.
+    /// Node ids are never reused.
+    pub fn remove_view(&mut self, node_id: usize) -> Option<V> {
+        let idx = self.idx(node_id)?;
+        self.node_ids.remove(idx);
+        Some(self.views.remove(idx))
     }
 
-    pub fn vector_clock_update(&mut self, node_id: usize, seq: Seq) {
-        self.vector_clock[node_id] = seq;
+    /// Returns the ids of the nodes currently in the composite, in order.
+    pub fn node_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.node_ids.iter().copied()
     }
 
-    pub fn views_mut(&mut self) -> &mut Vec<V> {
+    pub fn view_mut(&mut self, node_id: usize) -> Option<&mut V> {
+        let idx = self.idx(node_id)?;
+        Some(&mut self.views[idx])
+    }
+
+    /// Returns the views of the nodes currently in the composite, in node id order. A view's index here is its node id
+    /// until a view is removed; use `view_mut` to look one up by node id.
+    pub fn views_mut(&mut self) -> &mut [V] {
         &mut self.views
     }
+
+    /// Returns the index in `views` of the given node's view, or `None` if there's no such node.
+    fn idx(&self, node_id: usize) -> Option<usize> {
+        self.node_ids.binary_search(&node_id).ok()
+    }
+
+    /// Returns the vector clock entries of the nodes currently in the composite, with their node ids.
+    fn live_clock(&self) -> impl Iterator<Item = (usize, S)> + '_ {
+        self.node_ids()
+            .map(|node_id| (node_id, self.vector_clock[node_id]))
+    }
+
+    /// Records that the given node has promised not to assign sequence numbers at or below `seq`. Returns an error if
+    /// this would take back an earlier promise, or if there's no such node.
+    pub fn vector_clock_update(&mut self, node_id: usize, seq: S) -> Result<(), Error<S>> {
+        if self.idx(node_id).is_none() {
+            return Err(Error::UnknownNode { node_id });
+        }
+        let entry = &mut self.vector_clock[node_id];
+        if seq < *entry {
+            return Err(Error::ClockRegression { previous: *entry, next: seq });
+        }
+        *entry = seq;
+        Ok(())
+    }
+
+    /// Returns the vector clock, indexed by node id. Removed nodes keep their last entries, which no longer affect the
+    /// composite.
+    pub fn vector_clock(&self) -> &[S] {
+        &self.vector_clock
+    }
+
+    /// Returns the number of nodes currently in the composite.
+    pub fn node_count(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Returns the ids of the nodes whose vector clock entries are more than `threshold` behind the highest entry, e.g.
+    /// to alert when a node falls behind.
+    pub fn lagging_nodes(&self, threshold: S) -> Vec<usize> {
+        let max = self
+            .live_clock()
+            .map(|(_, seq)| seq)
+            .max()
+            .unwrap_or_default();
+        self.live_clock()
+            .filter(|(_, seq)| max.saturating_sub(*seq) > threshold)
+            .map(|(node_id, _)| node_id)
+            .collect()
+    }
+
+    /// Returns the sequence number up to which every node has promised its events, or `None` if there are no nodes.
+    /// Unlike `get_current_seq`, this distinguishes an empty composite from one whose nodes haven't promised anything.
+    pub fn frontier_seq(&self) -> Option<S> {
+        self.live_clock().map(|(_, seq)| seq).min()
+    }
+
+    /// Returns the node holding back the frontier, i.e. the one with the lowest vector clock entry (the lowest node id,
+    /// if there are several), or `None` if there are no nodes.
+    pub fn slowest_node(&self) -> Option<usize> {
+        self.live_clock()
+            .min_by_key(|(node_id, seq)| (*seq, *node_id))
+            .map(|(node_id, _)| node_id)
+    }
+
+    /// Returns a single node's vector clock entry, i.e. the sequence number up to which it has promised its events, rather
+    /// than the minimum over all nodes. Returns `None` if there's no such node, like `scan_node`.
+    pub fn node_current_seq(&self, node_id: usize) -> Option<S> {
+        self.idx(node_id).map(|_| self.vector_clock[node_id])
+    }
+
+    /// Scan a single constituent view, without merging in the others. Returns `None` if there's no such node.
+    pub fn scan_node(&mut self, node_id: usize, start: S, end: S) -> Option<V::Iterator> {
+        self.view_mut(node_id).map(|view| view.scan(start, end))
+    }
 }
 
-impl<V: View> View for CompositeView<V>
-where
-    for<'a> V::Iterator: Clone,
-{
+impl<V: View<S>, S: SeqType> CompositeView<V, S> {
+    /// Scan the view for events up to and including the current sequence number. These events are immutable, so the
+    /// result won't change on subsequent reads.
+    pub fn scan_committed(&mut self) -> CompositeViewIterator<V, S> {
+        let current_seq = self.get_current_seq();
+        self.scan(S::MIN, current_seq)
+    }
+}
+
+impl<V: View<S>, S: SeqType> View<S> for CompositeView<V, S> {
     type Event = V::Event;
-    type Iterator = CompositeViewIterator<V>;
+    type Iterator = CompositeViewIterator<V, S>;
 
-    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+    fn scan(&mut self, start: S, end: S) -> Self::Iterator {
         CompositeViewIterator::new(self, start, end)
     }
 
-    fn get_current_seq(&mut self) -> Seq {
+    fn prefetch(&mut self, start: S, end: S) {
+        for view in &mut self.views {
+            view.prefetch(start, end);
+        }
+    }
+
+    fn seq_upper_bound(&mut self) -> S {
+        // nodes' events may be past the frontier, so the bound is the highest of theirs rather than the current seq
+        self.views
+            .iter_mut()
+            .map(|view| view.seq_upper_bound())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn get_current_seq(&mut self) -> S {
         // current seq for the purposes of reading is the minimum of sequences in the vector clock.
         // the entry for a vector clock is only updated by a transmission from that node, which is a promise not to
         // assign lower sequence numbers to writes, so that the events before the minimum sequence number are immutable
-        self.vector_clock.iter().min().copied().unwrap_or_default()
+        self.frontier_seq().unwrap_or_default()
     }
 }
 
-pub struct CompositeViewIterator<V: View> {
+pub struct CompositeViewIterator<V: View<S>, S: SeqType = Seq> {
     iterators: Vec<V::Iterator>,
+    // the next unconsumed event from the front and back of each iterator, if it's been taken from the iterator yet
+    fronts: Vec<Option<(S, V::Event)>>,
+    backs: Vec<Option<(S, V::Event)>>,
+    // whether each iterator has returned None, after which it's not pulled again
+    exhausted: Vec<bool>,
+    // the event at the front, if `peek` has taken it
+    peeked: Option<(S, V::Event)>,
+    tie_break: TieBreak,
 }
 
-impl<'iter, V: View> CompositeViewIterator<V>
-where
-    V::Iterator: Clone,
-{
-    fn new(view: &'iter mut CompositeView<V>, start: Seq, end: Seq) -> Self {
-        // iterate each constituent view
-        Self {
-            iterators: view
-                .views
-                .iter_mut()
-                .map(|view| view.scan(start, end))
-                .collect(),
-        }
+impl<'iter, V: View<S>, S: SeqType> CompositeViewIterator<V, S> {
+    fn new(view: &'iter mut CompositeView<V, S>, start: S, end: S) -> Self {
+        // iterate each constituent view, skipping those with no events in range because they have no events past the
+        // lower bound of the scan. Only the views' upper bounds say so; their current seqs don't, e.g. a nested
+        // composite's events may be past its frontier
+        let iterators = view
+            .views
+            .iter_mut()
+            .filter_map(|view| {
+                if view.seq_upper_bound() <= start {
+                    None
+                } else {
+                    Some(view.scan(start, end))
+                }
+            })
+            .collect::<Vec<_>>();
+        let fronts = iterators.iter().map(|_| None).collect();
+        let backs = iterators.iter().map(|_| None).collect();
+        let exhausted = vec![false; iterators.len()];
+        Self { iterators, fronts, backs, exhausted, peeked: None, tie_break: view.tie_break }
     }
-}
 
-impl<V: View> Iterator for CompositeViewIterator<V>
-where
-    V::Iterator: Clone,
-{
-    type Item = (Seq, V::Event);
+    /// Returns the event the next call to `next` will return, without consuming it, e.g. to learn the next seq while
+    /// coordinating with an external clock. The event is kept, so the following `next` doesn't select it again.
+    pub fn peek(&mut self) -> Option<(S, &V::Event)> {
+        if self.peeked.is_none() {
+            self.peeked = self.take(true);
+        }
+        self.peeked.as_ref().map(|(seq, event)| (*seq, event))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let min_seq_idx = {
-            // clone iterators
-            let mut iterators = self.iterators.iter().cloned().collect::<Vec<_>>();
-
-            // which iterator has the next event with the lowest sequence number?
-            let mut min_seq = Seq::MAX;
-            let mut min_seq_idx = None;
-            for (idx, iter) in iterators.iter_mut().enumerate() {
-                if let Some((seq, _)) = iter.next() {
-                    // if there are multiple, prefer the lowest node index (break ties by node id)
-                    if seq < min_seq {
-                        min_seq = seq;
-                        min_seq_idx = Some(idx);
-                    }
+    /// Consumes the event with the lowest (or highest) sequence number among the fronts (or backs) of the iterators.
+    /// With `TieBreak::LowNodeFirst`, ties go to the lowest node index at the front and to the highest at the back, so
+    /// that events are ordered by (seq, node) from either end; `TieBreak::HighNodeFirst` flips both.
+    fn take(&mut self, front: bool) -> Option<(S, V::Event)> {
+        let later_node_wins = front != (self.tie_break == TieBreak::LowNodeFirst);
+        let mut selected_seq = S::MIN;
+        let mut selected_idx = None;
+        for (idx, iter) in self.iterators.iter_mut().enumerate() {
+            let (near, far) = if front {
+                (&mut self.fronts[idx], &mut self.backs[idx])
+            } else {
+                (&mut self.backs[idx], &mut self.fronts[idx])
+            };
+            // each step pulls only from iterators whose buffered event was consumed
+            if near.is_none() && !self.exhausted[idx] {
+                *near = if front { iter.next() } else { iter.next_back() };
+                self.exhausted[idx] = near.is_none();
+            }
+            // once an iterator is exhausted, its last event may already be buffered at the other end
+            if near.is_none() {
+                *near = far.take();
+            }
+            if let Some((seq, _)) = *near {
+                let selected = match selected_idx {
+                    None => true,
+                    Some(_) if seq == selected_seq => later_node_wins,
+                    Some(_) if front => seq < selected_seq,
+                    Some(_) => seq > selected_seq,
+                };
+                if selected {
+                    selected_seq = seq;
+                    selected_idx = Some(idx);
                 }
             }
+        }
 
-            min_seq_idx
-        };
-
-        // advance the iterator with the lowest sequence number and return the result if there is one
-        min_seq_idx.and_then(|idx| self.iterators[idx].next())
+        // consume the selected event and return it if there is one
+        let buffered = if front { &mut self.fronts } else { &mut self.backs };
+        selected_idx.and_then(|idx| buffered[idx].take())
     }
 }
 
-impl<V: View> DoubleEndedIterator for CompositeViewIterator<V>
-where
-    V::Iterator: Clone,
-{
-    fn next_back(&mut self) -> Option<Self::Item> {
-        let max_seq_idx = {
-            // clone iterators
-            let mut iterators = self.iterators.iter().cloned().collect::<Vec<_>>();
-
-            // which iterator has the next event with the highest sequence number?
-            let mut max_seq = Seq::MIN;
-            let mut max_seq_idx = None;
-            for (idx, iter) in iterators.iter_mut().enumerate() {
-                if let Some((seq, _)) = iter.next_back() {
-                    // if there are multiple, prefer the highest node index (break ties by node id)
-                    if seq >= max_seq {
-                        max_seq = seq;
-                        max_seq_idx = Some(idx);
-                    }
-                }
-            }
+impl<V: View<S>, S: SeqType> Iterator for CompositeViewIterator<V, S> {
+    type Item = (S, V::Event);
 
-            max_seq_idx
-        };
+    fn next(&mut self) -> Option<Self::Item> {
+        self.peeked.take().or_else(|| self.take(true))
+    }
+}
 
-        // advance the iterator with the highest sequence number and return the result if there is one
-        max_seq_idx.and_then(|idx| self.iterators[idx].next_back())
+impl<V: View<S>, S: SeqType> DoubleEndedIterator for CompositeViewIterator<V, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // the peeked event is the frontmost, so it's the last to come off the back
+        self.take(false).or_else(|| self.peeked.take())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CompositeView;
-    use crate::table::vec::VecTable;
+    use super::{CompositeView, TieBreak};
+    use crate::error::Error;
+    use crate::table::vec::{VecTable, VecTableIterator};
+    use crate::testing::fixtures::{CountingView, Counts};
     use crate::{Seq, Table, View};
+    use std::rc::Rc;
+
+    /// A view whose iterator can't be cloned, e.g. like one reading from a file.
+    struct NoCloneView(VecTable<i32>);
+
+    struct NoCloneIterator(VecTableIterator<i32>);
+
+    impl View for NoCloneView {
+        type Event = i32;
+        type Iterator = NoCloneIterator;
+
+        fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+            NoCloneIterator(self.0.scan(start_exclusive, end_inclusive))
+        }
+
+        fn get_current_seq(&mut self) -> Seq {
+            self.0.get_current_seq()
+        }
+    }
+
+    impl Iterator for NoCloneIterator {
+        type Item = (Seq, i32);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+    }
+
+    impl DoubleEndedIterator for NoCloneIterator {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.0.next_back()
+        }
+    }
 
     #[test]
     fn scan_none() {
@@ -141,6 +346,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn empty() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 5]);
+        assert_eq!(composite.get_current_seq(), 0);
+        assert_eq!(composite.first_seq(), None);
+        assert_eq!(composite.scan(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn no_views() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![]);
+        assert_eq!(composite.get_current_seq(), 0);
+        assert_eq!(composite.frontier_seq(), None);
+        assert_eq!(composite.slowest_node(), None);
+        assert_eq!(composite.first_seq(), None);
+        assert_eq!(composite.scan(Seq::MIN, Seq::MAX).count(), 0);
+        assert_eq!(composite.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(composite.scan_committed().count(), 0);
+        assert!(composite.scan_node(0, Seq::MIN, Seq::MAX).is_none());
+        assert!(matches!(
+            composite.vector_clock_update(0, 1),
+            Err(Error::UnknownNode { node_id: 0 })
+        ));
+    }
+
+    #[test]
+    fn slowest_node() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 3]);
+        assert_eq!(composite.frontier_seq(), Some(0));
+        assert_eq!(composite.slowest_node(), Some(0));
+
+        composite.vector_clock_update(0, 5).unwrap();
+        composite.vector_clock_update(2, 3).unwrap();
+        assert_eq!(composite.frontier_seq(), Some(0));
+        assert_eq!(composite.slowest_node(), Some(1));
+
+        composite.vector_clock_update(1, 4).unwrap();
+        assert_eq!(composite.frontier_seq(), Some(3));
+        assert_eq!(composite.slowest_node(), Some(2));
+        assert!(matches!(
+            composite.vector_clock_update(3, 1),
+            Err(Error::UnknownNode { node_id: 3 })
+        ));
+    }
+
     #[test]
     fn scan_one() {
         let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 5]);
@@ -234,4 +484,381 @@ mod tests {
             vec![12, 34, 56, 78, 90] // nodes don't matter in this case because seqs are unique
         );
     }
+
+    #[test]
+    fn scan_no_clone() {
+        let mut views = vec![VecTable::new(); 3];
+        views[0].append([12, 56]);
+        views[1].append([34, 90]);
+        views[2].append([78]);
+        let mut composite =
+            CompositeView::new(views.into_iter().map(NoCloneView).collect::<Vec<_>>());
+
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (1, 34), (1, 78), (2, 56), (2, 90)]
+        );
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(),
+            vec![(2, 90), (2, 56), (1, 78), (1, 34), (1, 12)]
+        );
+    }
+
+    #[test]
+    fn scan_skips_empty_nodes() {
+        let counts = Rc::new(Counts::default());
+        let mut composite = CompositeView::new(
+            (0..100)
+                .map(|_| CountingView::<i32>::new(counts.clone()))
+                .collect(),
+        );
+        composite.views[10].table.append([12, 56]);
+        composite.views[20].table.append([34]);
+
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (1, 34), (2, 56)]
+        );
+        assert_eq!(counts.scans.get(), 2);
+
+        // node 20 has nothing past seq 1
+        assert_eq!(composite.scan(1, Seq::MAX).rev().collect::<Vec<_>>(), vec![(2, 56)]);
+        assert_eq!(counts.scans.get(), 3);
+    }
+
+    #[test]
+    fn scan_nested() {
+        // the inner composites' nodes haven't promised anything, so their current seqs are 0, but their events are visible
+        let mut inner = vec![CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]); 2];
+        inner[0].views[0].append([12, 56]);
+        inner[0].views[1].append([34]);
+        inner[1].views[1].append([78]);
+        let mut composite = CompositeView::new(inner);
+
+        assert_eq!(composite.views[0].get_current_seq(), 0);
+        assert_eq!(composite.seq_upper_bound(), 2);
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (1, 34), (1, 78), (2, 56)]
+        );
+        assert_eq!(composite.scan(1, Seq::MAX).collect::<Vec<_>>(), vec![(2, 56)]);
+        assert_eq!(composite.scan(2, Seq::MAX).count(), 0);
+    }
+
+    #[test]
+    fn scan_many_nodes() {
+        const NODES: usize = 50;
+        const EVENTS_PER_NODE: usize = 100;
+
+        let counts = Rc::new(Counts::default());
+        let mut composite = CompositeView::new(
+            (0..NODES)
+                .map(|_| CountingView::<i32>::new(counts.clone()))
+                .collect(),
+        );
+        for (node_id, view) in composite.views_mut().iter_mut().enumerate() {
+            for i in 0..EVENTS_PER_NODE {
+                // nodes take turns assigning seqs, and every other round all nodes share a seq
+                let round = (i * NODES) as Seq;
+                let seq = if i % 2 == 0 { round + node_id as Seq } else { round };
+                view.table.set_current_seq(seq);
+                view.table.append([node_id as i32]);
+            }
+        }
+
+        let events = composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>();
+        assert_eq!(events.len(), NODES * EVENTS_PER_NODE);
+        // ordered by (seq, node)
+        assert!(events.windows(2).all(|pair| pair[0] <= pair[1]));
+        // each step pulls only from the iterator it consumed, plus one pull per iterator to find it exhausted
+        assert_eq!(counts.pulls.get(), NODES * EVENTS_PER_NODE + NODES);
+
+        counts.reset();
+        let reversed = composite.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>();
+        assert!(reversed.iter().rev().eq(events.iter()));
+        assert_eq!(counts.pulls.get(), NODES * EVENTS_PER_NODE + NODES);
+    }
+
+    #[test]
+    fn scan_both_ends() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+
+        composite.views[0].append([12, 56]);
+        composite.views[1].append([34]);
+
+        let mut iter = composite.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((1, 12)));
+        assert_eq!(iter.next_back(), Some((2, 56)));
+        assert_eq!(iter.next(), Some((1, 34)));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn peek() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+
+        composite.view_mut(0).unwrap().append([12, 56]);
+        composite.view_mut(1).unwrap().append([34]);
+
+        let mut iter = composite.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.peek(), Some((1, &12)));
+        assert_eq!(iter.peek(), Some((1, &12)));
+        assert_eq!(iter.next(), Some((1, 12)));
+        assert_eq!(iter.peek(), Some((1, &34)));
+        assert_eq!(iter.next(), Some((1, 34)));
+        assert_eq!(iter.next(), Some((2, 56)));
+        assert_eq!(iter.peek(), None);
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(composite.scan(Seq::MAX, Seq::MIN).peek(), None);
+
+        // the peeked event is the last to come off the back
+        let mut iter = composite.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.peek(), Some((1, &12)));
+        assert_eq!(iter.next_back(), Some((2, 56)));
+        assert_eq!(iter.next_back(), Some((1, 34)));
+        assert_eq!(iter.next_back(), Some((1, 12)));
+        assert_eq!(iter.peek(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn scan_rev() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+
+        composite.view_mut(0).unwrap().append([12, 56]);
+        composite.view_mut(1).unwrap().append([34]);
+
+        assert_eq!(
+            composite.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(2, 56), (1, 34), (1, 12)]
+        );
+        assert_eq!(composite.scan_rev(1, 2).collect::<Vec<_>>(), vec![(2, 56)]);
+        assert_eq!(composite.scan_rev(2, 1).count(), 0);
+        assert_eq!(composite.scan(2, 1).count(), 0);
+    }
+
+    #[test]
+    fn tie_break() {
+        for (tie_break, forward) in [
+            (TieBreak::LowNodeFirst, vec![(1, 12), (2, 34), (2, 56), (3, 78)]),
+            (TieBreak::HighNodeFirst, vec![(1, 12), (2, 56), (2, 34), (3, 78)]),
+        ] {
+            let mut composite =
+                CompositeView::<VecTable<i32>>::with_tie_break(vec![VecTable::new(); 2], tie_break);
+            composite.view_mut(0).unwrap().append([12, 34]);
+            composite.view_mut(1).unwrap().set_current_seq(1);
+            composite.view_mut(1).unwrap().append([56, 78]);
+            let reverse = forward.iter().copied().rev().collect::<Vec<_>>();
+
+            assert_eq!(composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), forward);
+            assert_eq!(composite.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(), reverse);
+            assert_eq!(composite.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), reverse);
+            assert_eq!(
+                composite
+                    .scan_rev(Seq::MIN, Seq::MAX)
+                    .rev()
+                    .collect::<Vec<_>>(),
+                forward
+            );
+
+            // alternating ends meet in the middle without reordering the tie
+            let mut iter = composite.scan(Seq::MIN, Seq::MAX);
+            let mut both_ends = vec![iter.next().unwrap(), iter.next_back().unwrap()];
+            both_ends.extend(iter);
+            assert_eq!(both_ends, vec![forward[0], forward[3], forward[1], forward[2]]);
+        }
+    }
+
+    #[test]
+    fn scan_node() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 3]);
+
+        composite.views[0].append([12, 56]);
+        composite.views[1].append([34, 78]);
+
+        assert_eq!(
+            composite
+                .scan_node(0, Seq::MIN, Seq::MAX)
+                .map(|iter| iter.collect::<Vec<_>>()),
+            Some(vec![(1, 12), (2, 56)])
+        );
+        assert_eq!(
+            composite
+                .scan_node(1, 1, Seq::MAX)
+                .map(|iter| iter.collect::<Vec<_>>()),
+            Some(vec![(2, 78)])
+        );
+        assert_eq!(
+            composite
+                .scan_node(2, Seq::MIN, Seq::MAX)
+                .map(|iter| iter.collect::<Vec<_>>()),
+            Some(vec![])
+        );
+        assert!(composite.scan_node(3, Seq::MIN, Seq::MAX).is_none());
+
+        composite.vector_clock_update(0, 2).unwrap();
+        composite.vector_clock_update(1, 1).unwrap();
+        assert_eq!(composite.get_current_seq(), 0);
+        assert_eq!(composite.node_current_seq(0), Some(2));
+        assert_eq!(composite.node_current_seq(1), Some(1));
+        assert_eq!(composite.node_current_seq(2), Some(0));
+        assert_eq!(composite.node_current_seq(3), None);
+        composite.remove_view(1);
+        assert_eq!(composite.node_current_seq(1), None);
+    }
+
+    #[test]
+    fn lagging_nodes() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 4]);
+        composite.vector_clock_update(0, 10).unwrap();
+        composite.vector_clock_update(1, 7).unwrap();
+        composite.vector_clock_update(2, 3).unwrap();
+
+        assert_eq!(composite.vector_clock(), &[10, 7, 3, 0]);
+        assert_eq!(composite.node_count(), 4);
+        assert_eq!(composite.lagging_nodes(3), vec![2, 3]);
+        assert_eq!(composite.lagging_nodes(2), vec![1, 2, 3]);
+        assert_eq!(composite.lagging_nodes(10), Vec::<usize>::new());
+
+        // removed nodes neither lag nor set the pace
+        composite.remove_view(0);
+        assert_eq!(composite.vector_clock(), &[10, 7, 3, 0]);
+        assert_eq!(composite.node_count(), 3);
+        assert_eq!(composite.lagging_nodes(4), vec![3]);
+
+        assert_eq!(
+            CompositeView::<VecTable<i32>>::new(vec![]).lagging_nodes(0),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn add_remove_view() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+        composite.view_mut(0).unwrap().append([12]);
+        composite.view_mut(1).unwrap().append([34, 56]);
+        composite.vector_clock_update(0, 3).unwrap();
+        composite.vector_clock_update(1, 3).unwrap();
+        assert_eq!(composite.get_current_seq(), 3);
+
+        // a new node holds back the current seq until it promises otherwise
+        let node_id = composite.add_view(VecTable::new());
+        assert_eq!(node_id, 2);
+        assert_eq!(composite.get_current_seq(), 0);
+        composite.view_mut(node_id).unwrap().append([78, 90]);
+        composite.vector_clock_update(node_id, 2).unwrap();
+        assert_eq!(composite.get_current_seq(), 2);
+        assert_eq!(composite.slowest_node(), Some(2));
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (1, 34), (1, 78), (2, 56), (2, 90)]
+        );
+
+        // removing an earlier node keeps the others' ids
+        let removed = composite.remove_view(0);
+        assert_eq!(removed.map(|mut view| view.get_current_seq()), Some(1));
+        assert!(composite.remove_view(0).is_none());
+        assert_eq!(composite.node_ids().collect::<Vec<_>>(), vec![1, 2]);
+        // views_mut holds only the live views, in node id order
+        assert_eq!(
+            composite
+                .views_mut()
+                .iter_mut()
+                .map(|view| view.get_current_seq())
+                .collect::<Vec<_>>(),
+            vec![2, 2]
+        );
+        assert_eq!(
+            composite.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 34), (1, 78), (2, 56), (2, 90)]
+        );
+        assert!(composite.scan_node(0, Seq::MIN, Seq::MAX).is_none());
+        assert_eq!(
+            composite
+                .scan_node(2, Seq::MIN, Seq::MAX)
+                .map(|iter| iter.collect::<Vec<_>>()),
+            Some(vec![(1, 78), (2, 90)])
+        );
+        assert!(matches!(
+            composite.vector_clock_update(0, 4),
+            Err(Error::UnknownNode { node_id: 0 })
+        ));
+
+        // the removed node's clock entry no longer holds back the current seq
+        composite.vector_clock_update(2, 3).unwrap();
+        assert_eq!(composite.get_current_seq(), 3);
+        assert_eq!(composite.add_view(VecTable::new()), 3);
+    }
+
+    #[test]
+    fn vector_clock_regression() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+        composite.vector_clock_update(0, 2).unwrap();
+        composite.vector_clock_update(0, 2).unwrap();
+        assert!(matches!(
+            composite.vector_clock_update(0, 1),
+            Err(Error::ClockRegression { previous: 2, next: 1 })
+        ));
+        composite.vector_clock_update(1, 1).unwrap();
+        assert_eq!(composite.get_current_seq(), 1);
+    }
+
+    #[test]
+    fn scan_committed() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+
+        composite.views[0].append([12, 56]);
+        composite.views[1].append([34, 78]);
+
+        assert_eq!(composite.scan_committed().collect::<Vec<_>>(), vec![]);
+
+        // one node reporting in doesn't commit anything because the other may still write at low seqs
+        composite.vector_clock_update(0, 2).unwrap();
+        assert_eq!(composite.get_current_seq(), 0);
+        assert_eq!(composite.scan_committed().collect::<Vec<_>>(), vec![]);
+
+        composite.vector_clock_update(1, 1).unwrap();
+        assert_eq!(composite.get_current_seq(), 1);
+        assert_eq!(composite.scan_committed().collect::<Vec<_>>(), vec![(1, 12), (1, 34)]);
+
+        // uncommitted writes are not exposed
+        composite.views[0].append([90]);
+        assert_eq!(composite.scan_committed().collect::<Vec<_>>(), vec![(1, 12), (1, 34)]);
+
+        composite.vector_clock_update(1, 3).unwrap();
+        assert_eq!(composite.get_current_seq(), 2);
+        assert_eq!(
+            composite.scan_committed().collect::<Vec<_>>(),
+            vec![(1, 12), (1, 34), (2, 56), (2, 78)]
+        );
+    }
+
+    #[test]
+    fn u128_seqs() {
+        let base = u64::MAX as u128;
+        let mut composite = CompositeView::new(vec![
+            VecTable::<i32, u128>::default(),
+            VecTable::<i32, u128>::default(),
+        ]);
+        for (node_id, view) in composite.views_mut().iter_mut().enumerate() {
+            view.set_current_seq(base);
+            view.append([node_id as i32, node_id as i32 + 10]);
+        }
+        composite.vector_clock_update(0, base + 2).unwrap();
+        composite.vector_clock_update(1, base + 1).unwrap();
+
+        assert_eq!(composite.get_current_seq(), base + 1);
+        assert_eq!(composite.lagging_nodes(0), vec![1]);
+        assert_eq!(
+            composite.scan(base, u128::MAX).collect::<Vec<_>>(),
+            vec![(base + 1, 0), (base + 1, 1), (base + 2, 10), (base + 2, 11)]
+        );
+        assert_eq!(composite.scan_committed().count(), 2);
+        assert!(matches!(
+            composite.vector_clock_update(1, base),
+            Err(Error::ClockRegression { previous, next }) if previous == base + 1 && next == base
+        ));
+    }
 }
@@ -0,0 +1,138 @@
+use crate::{Seq, View};
+
+/// Hides events equal to the event before them, e.g. duplicates from at-least-once delivery, keeping the first event of
+/// each run of equal events in sequence order. Iterating from the back yields the same events as iterating from the
+/// front, so `scan_rev` keeps the same sequence numbers as `scan`.
+pub struct DedupView<V: View> {
+    inner: V,
+}
+
+impl<V: View> DedupView<V> {
+    pub fn new(inner: V) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for DedupView<V>
+where
+    V::Event: Clone + PartialEq,
+{
+    type Event = V::Event;
+    type Iterator = DedupViewIterator<V>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        DedupViewIterator {
+            inner: self.inner.scan(start_exclusive, end_inclusive),
+            last_front: None,
+            back_peek: None,
+        }
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.inner.prefetch(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+pub struct DedupViewIterator<V: View> {
+    inner: V::Iterator,
+    // the event most recently yielded from the front
+    last_front: Option<V::Event>,
+    // the event taken from the back that ended the run most recently yielded from the back
+    back_peek: Option<(Seq, V::Event)>,
+}
+
+impl<V: View> Iterator for DedupViewIterator<V>
+where
+    V::Event: Clone + PartialEq,
+{
+    type Item = (Seq, V::Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (seq, event) = self.inner.next().or_else(|| self.back_peek.take())?;
+            if self.last_front.as_ref() != Some(&event) {
+                self.last_front = Some(event.clone());
+                return Some((seq, event));
+            }
+        }
+    }
+}
+
+impl<V: View> DoubleEndedIterator for DedupViewIterator<V>
+where
+    V::Event: Clone + PartialEq,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // walk back to the first event of the run at the back
+        let mut first = self.back_peek.take().or_else(|| self.inner.next_back())?;
+        loop {
+            match self.inner.next_back() {
+                Some(previous) if previous.1 == first.1 => first = previous,
+                Some(previous) => {
+                    self.back_peek = Some(previous);
+                    return Some(first);
+                }
+                None if self.last_front.as_ref() == Some(&first.1) => {
+                    // the run continues into events already yielded from the front
+                    return None;
+                }
+                None => return Some(first),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = DedupView::new(VecTable::<i32>::new());
+        view.inner_mut().append([1, 2, 2, 2, 3, 2]);
+
+        let forward = vec![(1, 1), (2, 2), (5, 3), (6, 2)];
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), forward);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(),
+            forward.into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(view.scan(2, 4).collect::<Vec<_>>(), vec![(3, 2)]);
+    }
+
+    #[test]
+    fn scan_both_ends() {
+        let mut view = DedupView::new(VecTable::<i32>::new());
+        view.inner_mut().append([1, 1, 1]);
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((1, 1)));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        view.inner_mut().append([2, 2, 3]);
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next_back(), Some((6, 3)));
+        assert_eq!(iter.next_back(), Some((4, 2)));
+        assert_eq!(iter.next(), Some((1, 1)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next_back(), Some((6, 3)));
+        assert_eq!(iter.next(), Some((1, 1)));
+        assert_eq!(iter.next(), Some((4, 2)));
+        assert_eq!(iter.next_back(), None);
+    }
+}
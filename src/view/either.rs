@@ -17,6 +17,13 @@ impl<Event, L: View<Event = Event>, R: View<Event = Event>> View for Either<L, R
         }
     }
 
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        match self {
+            Either::Left(left) => left.prefetch(start_exclusive, end_inclusive),
+            Either::Right(right) => right.prefetch(start_exclusive, end_inclusive),
+        }
+    }
+
     fn get_current_seq(&mut self) -> Seq {
         match self {
             Either::Left(left) => left.get_current_seq(),
@@ -49,6 +56,7 @@ impl<Event, L: View<Event = Event>, R: View<Event = Event>> Iterator
 {
     type Item = (Seq, Event);
 
+    #[allow(clippy::map_identity)]
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             EitherViewIterator::Left(left) => left.next().map(|(seq, event)| (seq, event)),
@@ -60,6 +68,7 @@ impl<Event, L: View<Event = Event>, R: View<Event = Event>> Iterator
 impl<Event, L: View<Event = Event>, R: View<Event = Event>> DoubleEndedIterator
     for EitherViewIterator<Event, L, R>
 {
+    #[allow(clippy::map_identity)]
     fn next_back(&mut self) -> Option<Self::Item> {
         match self {
             EitherViewIterator::Left(left) => left.next_back().map(|(seq, event)| (seq, event)),
@@ -67,3 +76,36 @@ impl<Event, L: View<Event = Event>, R: View<Event = Event>> DoubleEndedIterator
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use either::Either;
+
+    use crate::table::explicit_seq::ExplicitSeqTable;
+    use crate::table::vec::VecTable;
+    use crate::{Table, View};
+
+    #[test]
+    fn empty() {
+        let mut left = Either::<VecTable<i32>, ExplicitSeqTable<i32>>::Left(VecTable::new());
+        assert_eq!(left.get_current_seq(), 0);
+        assert_eq!(left.first_seq(), None);
+        assert_eq!(left.scan(0, 0).count(), 0);
+
+        let mut right =
+            Either::<VecTable<i32>, ExplicitSeqTable<i32>>::Right(ExplicitSeqTable::new());
+        assert_eq!(right.get_current_seq(), 0);
+        assert_eq!(right.first_seq(), None);
+        assert_eq!(right.scan(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn first_seq() {
+        let mut table = ExplicitSeqTable::new();
+        table.append_at(5, 12);
+        table.append([34]);
+        let mut view = Either::<VecTable<i32>, ExplicitSeqTable<i32>>::Right(table);
+        assert_eq!(view.get_current_seq(), 6);
+        assert_eq!(view.first_seq(), Some(5));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Seq, View};
+
+/// Exposes only the inner view's events matching a predicate, with their original sequence numbers, without copying the
+/// inner view.
+pub struct FilterView<V: View> {
+    inner: V,
+    predicate: fn(&V::Event) -> bool,
+}
+
+impl<V: View> FilterView<V> {
+    pub fn new(inner: V, predicate: fn(&V::Event) -> bool) -> Self {
+        Self { inner, predicate }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for FilterView<V> {
+    type Event = V::Event;
+    type Iterator = FilterViewIterator<V>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        FilterViewIterator {
+            inner: self.inner.scan(start_exclusive, end_inclusive),
+            predicate: self.predicate,
+        }
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.inner.prefetch(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+pub struct FilterViewIterator<V: View> {
+    inner: V::Iterator,
+    predicate: fn(&V::Event) -> bool,
+}
+
+impl<V: View> Iterator for FilterViewIterator<V> {
+    type Item = (Seq, V::Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // both ends consume from the same inner iterator, so they can't pass each other
+        let predicate = self.predicate;
+        self.inner.find(|(_, event)| predicate(event))
+    }
+}
+
+impl<V: View> DoubleEndedIterator for FilterViewIterator<V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate;
+        self.inner.rfind(|(_, event)| predicate(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    fn even(event: &i32) -> bool {
+        event % 2 == 0
+    }
+
+    #[test]
+    fn scan() {
+        let mut view = FilterView::new(VecTable::<i32>::new(), even);
+        view.inner_mut().append([1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(view.get_current_seq(), 6);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(2, 2), (4, 4), (6, 6)]);
+        assert_eq!(view.scan_rev(1, 6).collect::<Vec<_>>(), vec![(6, 6), (4, 4), (2, 2)]);
+        assert_eq!(view.scan(2, 5).collect::<Vec<_>>(), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn get_event() {
+        let mut view = FilterView::new(VecTable::<i32>::new(), even);
+        view.inner_mut().append([1, 2, 3, 4]);
+
+        assert_eq!(view.get_event(2), Some((2, 2)));
+        assert_eq!(view.get_event(4), Some((4, 4)));
+
+        // filtered out, unassigned, and boundary seqs
+        assert_eq!(view.get_event(3), None);
+        assert_eq!(view.get_event(5), None);
+        assert_eq!(view.get_event(Seq::MIN), None);
+        assert_eq!(view.get_event(Seq::MAX), None);
+    }
+
+    #[test]
+    fn scan_both_ends() {
+        let mut view = FilterView::new(VecTable::<i32>::new(), even);
+        view.inner_mut().append([1, 2, 3, 4, 5, 6, 7]);
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((2, 2)));
+        assert_eq!(iter.next_back(), Some((6, 6)));
+        assert_eq!(iter.next_back(), Some((4, 4)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+}
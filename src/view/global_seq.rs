@@ -0,0 +1,137 @@
+use crate::table::vec::{VecTable, VecTableIterator};
+use crate::view::composite::CompositeView;
+use crate::{Seq, Table, View};
+
+/// Renumbers the events of a composite view to a single dense global sequence (1, 2, 3, ...) in merged order, so
+/// indexes that expect one monotonic sequence can consume it. Only committed events are renumbered, because
+/// uncommitted ones may still be preceded by writes from other nodes.
+pub struct GlobalSeqView<V: View>
+where
+    V::Event: Clone,
+{
+    composite: CompositeView<V>,
+    committed_seq: Seq,
+    table: VecTable<V::Event>,
+    origins: Vec<(usize, Seq)>,
+}
+
+impl<V: View> GlobalSeqView<V>
+where
+    V::Event: Clone,
+{
+    pub fn new(composite: CompositeView<V>) -> Self {
+        Self { composite, committed_seq: 0, table: VecTable::new(), origins: Vec::new() }
+    }
+
+    pub fn composite_mut(&mut self) -> &mut CompositeView<V> {
+        &mut self.composite
+    }
+
+    /// Returns the node and original sequence number of the event with the given global sequence number.
+    pub fn origin(&self, global_seq: Seq) -> Option<(usize, Seq)> {
+        let idx = global_seq.checked_sub(1)? as usize;
+        self.origins.get(idx).copied()
+    }
+
+    /// Assigns global sequence numbers to events committed since the last refresh.
+    fn refresh(&mut self) {
+        let committed_seq = self.composite.get_current_seq();
+        if committed_seq <= self.committed_seq {
+            return;
+        }
+
+        // merge newly committed events in (seq, node) order, matching the composite's scan order
+        let mut events = Vec::new();
+        for node_id in self.composite.node_ids().collect::<Vec<_>>() {
+            if let Some(iter) = self
+                .composite
+                .scan_node(node_id, self.committed_seq, committed_seq)
+            {
+                events.extend(iter.map(|(seq, event)| (seq, node_id, event)));
+            }
+        }
+        events.sort_by_key(|(seq, node_id, _)| (*seq, *node_id));
+
+        for (seq, node_id, event) in events {
+            self.table.append([event]);
+            self.origins.push((node_id, seq));
+        }
+        self.committed_seq = committed_seq;
+    }
+}
+
+impl<V: View> View for GlobalSeqView<V>
+where
+    V::Event: Clone,
+{
+    type Event = V::Event;
+    type Iterator = VecTableIterator<V::Event>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        self.refresh();
+        self.table.scan(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.refresh();
+        self.table.get_current_seq()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlobalSeqView;
+    use crate::table::vec::VecTable;
+    use crate::view::composite::CompositeView;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 3]);
+        composite.views_mut()[0].append([12, 56]);
+        composite.views_mut()[1].append([34, 90]);
+        composite.views_mut()[2].append([78]);
+        let mut view = GlobalSeqView::new(composite);
+
+        // nothing is committed yet
+        assert_eq!(view.get_current_seq(), 0);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).count(), 0);
+
+        for node_id in 0..3 {
+            view.composite_mut()
+                .vector_clock_update(node_id, 2)
+                .unwrap();
+        }
+        assert_eq!(view.get_current_seq(), 5);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (2, 34), (3, 78), (4, 56), (5, 90)]
+        );
+        assert_eq!(view.scan(2, 4).collect::<Vec<_>>(), vec![(3, 78), (4, 56)]);
+    }
+
+    #[test]
+    fn origin() {
+        let mut composite = CompositeView::<VecTable<i32>>::new(vec![VecTable::new(); 2]);
+        composite.views_mut()[0].append([12, 56]);
+        composite.views_mut()[1].append([34]);
+        composite.vector_clock_update(0, 1).unwrap();
+        composite.vector_clock_update(1, 1).unwrap();
+        let mut view = GlobalSeqView::new(composite);
+
+        assert_eq!(view.get_current_seq(), 2);
+        assert_eq!(view.origin(0), None);
+        assert_eq!(view.origin(1), Some((0, 1)));
+        assert_eq!(view.origin(2), Some((1, 1)));
+        assert_eq!(view.origin(3), None);
+
+        // later commits continue the dense sequence
+        view.composite_mut().views_mut()[1].append([78]);
+        view.composite_mut().vector_clock_update(0, 2).unwrap();
+        view.composite_mut().vector_clock_update(1, 2).unwrap();
+        assert_eq!(view.get_current_seq(), 4);
+        assert_eq!(view.scan(2, Seq::MAX).collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+        assert_eq!(view.origin(3), Some((0, 2)));
+        assert_eq!(view.origin(4), Some((1, 2)));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{Seq, View};
+
+/// Exposes only events at least `lag` sequence numbers behind the inner view's current sequence number, giving readers
+/// a stable horizon that advances smoothly as newer events arrive.
+pub struct LaggingView<V: View> {
+    inner: V,
+    lag: Seq,
+}
+
+impl<V: View> LaggingView<V> {
+    pub fn with_lag(inner: V, lag: Seq) -> Self {
+        Self { inner, lag }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for LaggingView<V> {
+    type Event = V::Event;
+    type Iterator = V::Iterator;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        // events past the horizon are hidden
+        let horizon = self.get_current_seq();
+        self.inner.scan(start, end.min(horizon))
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq().saturating_sub(self.lag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LaggingView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = LaggingView::with_lag(VecTable::<i32>::new(), 2);
+
+        view.inner_mut().append([12, 34]);
+        assert_eq!(view.get_current_seq(), 0);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).count(), 0);
+
+        view.inner_mut().append([56]);
+        assert_eq!(view.get_current_seq(), 1);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12)]);
+
+        view.inner_mut().append([78, 90]);
+        assert_eq!(view.get_current_seq(), 3);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (2, 34), (3, 56)]
+        );
+        assert_eq!(view.scan(2, 5).collect::<Vec<_>>(), vec![(3, 56)]);
+        assert_eq!(view.scan_rev(1, 5).collect::<Vec<_>>(), vec![(3, 56), (2, 34)]);
+        assert_eq!(view.scan(5, 1).count(), 0);
+        assert_eq!(view.scan(3, 5).count(), 0);
+    }
+}
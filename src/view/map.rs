@@ -0,0 +1,82 @@
+use crate::{Seq, View};
+
+/// Exposes the inner view's events transformed by a function, with their original sequence numbers, e.g. to index a
+/// projection of a log without rewriting it. Events are mapped lazily as they're scanned, and each scan produces newly
+/// mapped values.
+pub struct MapView<V: View, T> {
+    inner: V,
+    f: fn(&V::Event) -> T,
+}
+
+impl<V: View, T> MapView<V, T> {
+    pub fn new(inner: V, f: fn(&V::Event) -> T) -> Self {
+        Self { inner, f }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View, T> View for MapView<V, T> {
+    type Event = T;
+    type Iterator = MapViewIterator<V, T>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        MapViewIterator { inner: self.inner.scan(start_exclusive, end_inclusive), f: self.f }
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.inner.prefetch(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+pub struct MapViewIterator<V: View, T> {
+    inner: V::Iterator,
+    f: fn(&V::Event) -> T,
+}
+
+impl<V: View, T> Iterator for MapViewIterator<V, T> {
+    type Item = (Seq, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(seq, event)| (seq, (self.f)(&event)))
+    }
+}
+
+impl<V: View, T> DoubleEndedIterator for MapViewIterator<V, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(seq, event)| (seq, (self.f)(&event)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MapView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = MapView::new(VecTable::<i32>::new(), i32::to_string);
+        view.inner_mut().append([12, 34, 56]);
+
+        assert_eq!(view.get_current_seq(), 3);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, "12".to_string()), (2, "34".to_string()), (3, "56".to_string())]
+        );
+        assert_eq!(
+            view.scan_rev(1, 3).collect::<Vec<_>>(),
+            vec![(3, "56".to_string()), (2, "34".to_string())]
+        );
+    }
+}
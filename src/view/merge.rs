@@ -0,0 +1,164 @@
+use std::marker::PhantomData;
+
+use crate::{Seq, View};
+
+/// Merges two views of the same events, which may be of different types, ordering events by seq and breaking ties toward
+/// the left view. `CompositeView` merges any number of views of a single type.
+pub struct MergeView<L: View<Event = E>, R: View<Event = E>, E> {
+    left: L,
+    right: R,
+    _event: PhantomData<E>,
+}
+
+impl<L: View<Event = E>, R: View<Event = E>, E> MergeView<L, R, E> {
+    pub fn new(left: L, right: R) -> Self {
+        Self { left, right, _event: PhantomData }
+    }
+
+    pub fn left_mut(&mut self) -> &mut L {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut R {
+        &mut self.right
+    }
+}
+
+impl<L: View<Event = E>, R: View<Event = E>, E> View for MergeView<L, R, E> {
+    type Event = E;
+    type Iterator = MergeViewIterator<L, R, E>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        MergeViewIterator {
+            left: Buffered::new(self.left.scan(start_exclusive, end_inclusive)),
+            right: Buffered::new(self.right.scan(start_exclusive, end_inclusive)),
+        }
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.left.prefetch(start_exclusive, end_inclusive);
+        self.right.prefetch(start_exclusive, end_inclusive);
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.left
+            .get_current_seq()
+            .min(self.right.get_current_seq())
+    }
+}
+
+/// An iterator with its next unconsumed event from the front and back, if it's been taken from the iterator yet.
+struct Buffered<I: DoubleEndedIterator> {
+    iter: I,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+}
+
+impl<I: DoubleEndedIterator> Buffered<I> {
+    fn new(iter: I) -> Self {
+        Self { iter, front: None, back: None }
+    }
+
+    fn peek_front(&mut self) -> Option<&I::Item> {
+        // once the iterator is exhausted, its last event may already be buffered at the back
+        if self.front.is_none() {
+            self.front = self.iter.next().or_else(|| self.back.take());
+        }
+        self.front.as_ref()
+    }
+
+    fn peek_back(&mut self) -> Option<&I::Item> {
+        // once the iterator is exhausted, its last event may already be buffered at the front
+        if self.back.is_none() {
+            self.back = self.iter.next_back().or_else(|| self.front.take());
+        }
+        self.back.as_ref()
+    }
+}
+
+pub struct MergeViewIterator<L: View<Event = E>, R: View<Event = E>, E> {
+    left: Buffered<L::Iterator>,
+    right: Buffered<R::Iterator>,
+}
+
+impl<L: View<Event = E>, R: View<Event = E>, E> MergeViewIterator<L, R, E> {
+    /// Takes the next event from the front or back of the iterators, whichever has the lowest (or highest) seq. Ties go
+    /// to the left view at the front and to the right at the back, so the two orders mirror each other.
+    fn take(&mut self, front: bool) -> Option<(Seq, E)> {
+        let (left, right) = if front {
+            (self.left.peek_front(), self.right.peek_front())
+        } else {
+            (self.left.peek_back(), self.right.peek_back())
+        };
+        let take_left = match (left, right) {
+            (Some((left, _)), Some((right, _))) => {
+                if front {
+                    left <= right
+                } else {
+                    left > right
+                }
+            }
+            (left, _) => left.is_some(),
+        };
+        match (take_left, front) {
+            (true, true) => self.left.front.take(),
+            (true, false) => self.left.back.take(),
+            (false, true) => self.right.front.take(),
+            (false, false) => self.right.back.take(),
+        }
+    }
+}
+
+impl<L: View<Event = E>, R: View<Event = E>, E> Iterator for MergeViewIterator<L, R, E> {
+    type Item = (Seq, E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.take(true)
+    }
+}
+
+impl<L: View<Event = E>, R: View<Event = E>, E> DoubleEndedIterator for MergeViewIterator<L, R, E> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.take(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergeView;
+    use crate::table::explicit_seq::ExplicitSeqTable;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = MergeView::new(VecTable::<&str>::new(), ExplicitSeqTable::<&str>::new());
+        view.left_mut().append(["l1", "l2"]);
+        view.left_mut().set_current_seq(4);
+        view.left_mut().append(["l5"]);
+        view.right_mut().append_at(2, "r2");
+        view.right_mut().append_at(3, "r3");
+        view.right_mut().append_at(6, "r6");
+
+        assert_eq!(view.get_current_seq(), 5);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, "l1"), (2, "l2"), (2, "r2"), (3, "r3"), (5, "l5"), (6, "r6")]
+        );
+        assert_eq!(
+            view.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(6, "r6"), (5, "l5"), (3, "r3"), (2, "r2"), (2, "l2"), (1, "l1")]
+        );
+        assert_eq!(view.scan(1, 3).collect::<Vec<_>>(), vec![(2, "l2"), (2, "r2"), (3, "r3")]);
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((1, "l1")));
+        assert_eq!(iter.next_back(), Some((6, "r6")));
+        assert_eq!(iter.next_back(), Some((5, "l5")));
+        assert_eq!(iter.next(), Some((2, "l2")));
+        assert_eq!(iter.next_back(), Some((3, "r3")));
+        assert_eq!(iter.next_back(), Some((2, "r2")));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+}
@@ -1,2 +1,17 @@
+pub mod chain;
 pub mod composite;
+pub mod dedup;
 pub mod either;
+pub mod filter;
+pub mod global_seq;
+pub mod lagging;
+pub mod map;
+pub mod merge;
+pub mod one_of;
+pub mod pairwise;
+pub mod read_only;
+pub mod skip;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod take;
+pub mod window;
@@ -0,0 +1,125 @@
+use crate::{Seq, View};
+
+/// Defines a view enum over several view types sharing an event type, delegating to the active variant, along with its
+/// iterator enum. Like `Either`'s view impl, but for views of more than two types chosen at runtime.
+macro_rules! one_of {
+    (
+        $(#[$attr:meta])*
+        $name:ident, $iterator:ident, $first:ident($First:ident) $(, $variant:ident($Variant:ident))+
+    ) => {
+        $(#[$attr])*
+        pub enum $name<$First, $($Variant),+> {
+            $first($First),
+            $($variant($Variant)),+
+        }
+
+        impl<$First: View, $($Variant: View<Event = $First::Event>),+> View for $name<$First, $($Variant),+> {
+            type Event = $First::Event;
+            type Iterator = $iterator<$First, $($Variant),+>;
+
+            fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+                match self {
+                    Self::$first(view) => $iterator::$first(view.scan(start_exclusive, end_inclusive)),
+                    $(Self::$variant(view) => $iterator::$variant(view.scan(start_exclusive, end_inclusive))),+
+                }
+            }
+
+            fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+                match self {
+                    Self::$first(view) => view.prefetch(start_exclusive, end_inclusive),
+                    $(Self::$variant(view) => view.prefetch(start_exclusive, end_inclusive)),+
+                }
+            }
+
+            fn get_current_seq(&mut self) -> Seq {
+                match self {
+                    Self::$first(view) => view.get_current_seq(),
+                    $(Self::$variant(view) => view.get_current_seq()),+
+                }
+            }
+        }
+
+        pub enum $iterator<$First: View, $($Variant: View),+> {
+            $first($First::Iterator),
+            $($variant($Variant::Iterator)),+
+        }
+
+        impl<$First: View, $($Variant: View<Event = $First::Event>),+> Iterator for $iterator<$First, $($Variant),+> {
+            type Item = (Seq, $First::Event);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                match self {
+                    Self::$first(iter) => iter.next(),
+                    $(Self::$variant(iter) => iter.next()),+
+                }
+            }
+        }
+
+        impl<$First: View, $($Variant: View<Event = $First::Event>),+> DoubleEndedIterator
+            for $iterator<$First, $($Variant),+>
+        {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                match self {
+                    Self::$first(iter) => iter.next_back(),
+                    $(Self::$variant(iter) => iter.next_back()),+
+                }
+            }
+        }
+    };
+}
+
+one_of!(
+    /// A view that is one of three view types with the same event type.
+    OneOf3, OneOf3Iterator, First(A), Second(B), Third(C)
+);
+
+one_of!(
+    /// A view that is one of four view types with the same event type.
+    OneOf4, OneOf4Iterator, First(A), Second(B), Third(C), Fourth(D)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{OneOf3, OneOf4};
+    use crate::table::vec::VecTable;
+    use crate::view::lagging::LaggingView;
+    use crate::{Seq, Table, View};
+
+    type Three = OneOf3<VecTable<i32>, VecTable<i32>, VecTable<i32>>;
+
+    fn table(events: &[i32]) -> VecTable<i32> {
+        let mut table = VecTable::new();
+        table.append(events.iter().copied());
+        table
+    }
+
+    #[test]
+    fn scan() {
+        let views: [Three; 3] = [
+            OneOf3::First(table(&[1, 2])),
+            OneOf3::Second(table(&[3, 4, 5])),
+            OneOf3::Third(table(&[6])),
+        ];
+        let expected = [vec![(1, 1), (2, 2)], vec![(1, 3), (2, 4), (3, 5)], vec![(1, 6)]];
+
+        for (mut view, expected) in views.into_iter().zip(expected) {
+            assert_eq!(view.get_current_seq(), expected.len() as Seq);
+            assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), expected);
+
+            let mut reversed = expected.clone();
+            reversed.reverse();
+            assert_eq!(view.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(), reversed);
+            assert_eq!(view.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), reversed);
+            assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+        }
+    }
+
+    #[test]
+    fn distinct_types() {
+        let mut view = OneOf4::<VecTable<i32>, LaggingView<VecTable<i32>>, Three, Three>::Second(
+            LaggingView::with_lag(table(&[1, 2, 3]), 1),
+        );
+        assert_eq!(view.get_current_seq(), 2);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 1), (2, 2)]);
+    }
+}
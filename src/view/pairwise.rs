@@ -0,0 +1,80 @@
+use crate::{Seq, View};
+
+/// Pairs each event of the inner view with the one scanned before it, e.g. to compute deltas between successive events.
+/// It isn't itself a `View`, as its scans yield pairs rather than events.
+pub struct PairwiseView<V: View> {
+    inner: V,
+}
+
+impl<V: View> PairwiseView<V> {
+    pub fn new(inner: V) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+
+    /// Scan the inner view for events between the given sequences, each paired with its predecessor. The first event's
+    /// predecessor is `None`, even if the view has events before `start_exclusive`.
+    pub fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Pairwise<V> {
+        Pairwise { inner: self.inner.scan(start_exclusive, end_inclusive), previous: None }
+    }
+}
+
+/// Yields `(seq, event, previous)` for each event of a scan, in sequence order. Events are owned, as the inner view's
+/// iterator yields them, so `previous` is a clone of the event before, kept from the last step; each event is cloned
+/// once. Pairing is defined by forward iteration, so the iterator isn't double-ended.
+pub struct Pairwise<V: View> {
+    inner: V::Iterator,
+    previous: Option<V::Event>,
+}
+
+impl<V: View> Iterator for Pairwise<V>
+where
+    V::Event: Clone,
+{
+    type Item = (Seq, V::Event, Option<V::Event>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (seq, event) = self.inner.next()?;
+        let previous = self.previous.replace(event.clone());
+        Some((seq, event, previous))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PairwiseView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table};
+
+    #[test]
+    fn scan() {
+        let mut view = PairwiseView::new(VecTable::<i32>::new());
+        view.inner_mut().append([12, 34, 56]);
+
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12, None), (2, 34, Some(12)), (3, 56, Some(34))]
+        );
+        assert_eq!(view.scan(1, 3).collect::<Vec<_>>(), vec![(2, 34, None), (3, 56, Some(34))]);
+        assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX)
+                .map(|(_, event, previous)| previous.map(|previous| event - previous))
+                .collect::<Vec<_>>(),
+            vec![None, Some(22), Some(22)]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut view = PairwiseView::new(VecTable::<i32>::new());
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).count(), 0);
+    }
+}
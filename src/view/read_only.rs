@@ -0,0 +1,51 @@
+use crate::{Seq, View};
+
+/// Wraps a view so it can be handed to other code for reading only. `ReadOnly` implements `View` but not `Table`, and
+/// does not expose the inner view, so holders of a `ReadOnly` cannot append to it.
+pub struct ReadOnly<V: View> {
+    inner: V,
+}
+
+impl<V: View> ReadOnly<V> {
+    pub fn new(inner: V) -> Self {
+        Self { inner }
+    }
+}
+
+impl<V: View> View for ReadOnly<V> {
+    type Event = V::Event;
+    type Iterator = V::Iterator;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        self.inner.scan(start, end)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+
+    fn prefetch(&mut self, start: Seq, end: Seq) {
+        self.inner.prefetch(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadOnly;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut table = VecTable::<i32>::new();
+        table.append([12, 34, 56]);
+
+        let mut view = ReadOnly::new(table);
+        assert_eq!(view.get_current_seq(), 3);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(1, 12), (2, 34), (3, 56)]
+        );
+        assert_eq!(view.scan_rev(1, 3).collect::<Vec<_>>(), vec![(3, 56), (2, 34)]);
+    }
+}
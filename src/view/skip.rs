@@ -0,0 +1,88 @@
+use crate::{Seq, View};
+
+/// Drops the first `n` events of each scan of the inner view, i.e. the `n` events with the lowest sequence numbers in the
+/// scan's range. Reversing the resulting iterator, e.g. with `scan_rev`, still drops those same `n` events, not the last
+/// `n` of the range.
+pub struct SkipView<V: View> {
+    inner: V,
+    n: usize,
+}
+
+impl<V: View> SkipView<V> {
+    pub fn new(inner: V, n: usize) -> Self {
+        Self { inner, n }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for SkipView<V> {
+    type Event = V::Event;
+    type Iterator = SkipViewIterator<V>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        SkipViewIterator { inner: self.inner.scan(start_exclusive, end_inclusive), to_skip: self.n }
+    }
+
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        self.inner.prefetch(start_exclusive, end_inclusive)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+pub struct SkipViewIterator<V: View> {
+    inner: V::Iterator,
+    // how many events at the front of the inner iterator have yet to be dropped
+    to_skip: usize,
+}
+
+impl<V: View> SkipViewIterator<V> {
+    fn skip_front(&mut self) {
+        let to_skip = std::mem::take(&mut self.to_skip);
+        for _ in 0..to_skip {
+            self.inner.next();
+        }
+    }
+}
+
+impl<V: View> Iterator for SkipViewIterator<V> {
+    type Item = (Seq, V::Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_front();
+        self.inner.next()
+    }
+}
+
+impl<V: View> DoubleEndedIterator for SkipViewIterator<V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // drop the front first so the back can't reach into it
+        self.skip_front();
+        self.inner.next_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkipView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = SkipView::new(VecTable::<i32>::new(), 2);
+        view.inner_mut().append([12, 34, 56, 78]);
+
+        assert_eq!(view.get_current_seq(), 4);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(3, 56), (4, 78)]);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(), vec![(4, 78), (3, 56)]);
+        assert_eq!(view.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(4, 78), (3, 56)]);
+        assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(view.scan(2, Seq::MAX).count(), 0);
+    }
+}
@@ -0,0 +1,63 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+
+use crate::{Seq, View};
+
+/// How many events a `ScanStream` yields before yielding to the executor.
+const YIELD_INTERVAL: usize = 64;
+
+/// Scan the view for events between the given sequences as a stream. The stream periodically yields to the executor so
+/// that scanning a large log doesn't block other tasks.
+pub fn scan_stream<V: View>(
+    view: &mut V, start_exclusive: Seq, end_inclusive: Seq,
+) -> ScanStream<V> {
+    ScanStream { iter: view.scan(start_exclusive, end_inclusive), since_yield: 0 }
+}
+
+pub struct ScanStream<V: View> {
+    iter: V::Iterator,
+    since_yield: usize,
+}
+
+// the stream never pins its iterator, which it owns
+impl<V: View> Unpin for ScanStream<V> {}
+
+impl<V: View> Stream for ScanStream<V> {
+    type Item = (Seq, V::Event);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.since_yield >= YIELD_INTERVAL {
+            // give other tasks a turn, asking to be polled again right away
+            self.since_yield = 0;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.since_yield += 1;
+        Poll::Ready(self.iter.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use super::scan_stream;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan_stream_matches_scan() {
+        let mut table = VecTable::<i32>::new();
+        table.append(0..1000);
+
+        let streamed = block_on(scan_stream(&mut table, Seq::MIN, Seq::MAX).collect::<Vec<_>>());
+        assert_eq!(streamed, table.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>());
+
+        let streamed = block_on(scan_stream(&mut table, 10, 20).collect::<Vec<_>>());
+        assert_eq!(streamed, table.scan(10, 20).collect::<Vec<_>>());
+    }
+}
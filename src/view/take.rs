@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use crate::{Seq, View};
+
+/// Limits each scan of the inner view to its first `n` events, i.e. the `n` events with the lowest sequence numbers in the
+/// scan's range. Reversing the resulting iterator, e.g. with `scan_rev`, yields those same `n` events in the opposite
+/// order, not the last `n` of the range.
+pub struct TakeView<V: View> {
+    inner: V,
+    n: usize,
+}
+
+impl<V: View> TakeView<V> {
+    pub fn new(inner: V, n: usize) -> Self {
+        Self { inner, n }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for TakeView<V> {
+    type Event = V::Event;
+    type Iterator = TakeViewIterator<V>;
+
+    fn scan(&mut self, start_exclusive: Seq, end_inclusive: Seq) -> Self::Iterator {
+        TakeViewIterator {
+            inner: self.inner.scan(start_exclusive, end_inclusive),
+            remaining: self.n,
+            taken: None,
+        }
+    }
+
+    /// Prefetches no further than `n` sequence numbers past `start_exclusive`, where the first `n` events end if the
+    /// inner view's sequence numbers are contiguous. Sparser events past that are read on demand.
+    fn prefetch(&mut self, start_exclusive: Seq, end_inclusive: Seq) {
+        if self.n > 0 {
+            let limit = start_exclusive.saturating_add(self.n as Seq);
+            self.inner
+                .prefetch(start_exclusive, end_inclusive.min(limit))
+        }
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+pub struct TakeViewIterator<V: View> {
+    inner: V::Iterator,
+    // how many more events may be taken from the front of the inner iterator
+    remaining: usize,
+    // once iterated from the back, the rest of the first n events, which must be taken from the front to find the back
+    taken: Option<VecDeque<(Seq, V::Event)>>,
+}
+
+impl<V: View> Iterator for TakeViewIterator<V> {
+    type Item = (Seq, V::Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(taken) = &mut self.taken {
+            return taken.pop_front();
+        }
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.inner.next()
+    }
+}
+
+impl<V: View> DoubleEndedIterator for TakeViewIterator<V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let remaining = std::mem::take(&mut self.remaining);
+        let inner = &mut self.inner;
+        self.taken
+            .get_or_insert_with(|| inner.take(remaining).collect())
+            .pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TakeView;
+    use crate::table::vec::VecTable;
+    use crate::testing::fixtures::CountingView;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = TakeView::new(VecTable::<i32>::new(), 2);
+        view.inner_mut().append([12, 34, 56, 78]);
+
+        assert_eq!(view.get_current_seq(), 4);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 12), (2, 34)]);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).rev().collect::<Vec<_>>(), vec![(2, 34), (1, 12)]);
+        assert_eq!(view.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(2, 34), (1, 12)]);
+        assert_eq!(view.scan(Seq::MAX, Seq::MIN).count(), 0);
+        assert_eq!(view.scan(3, Seq::MAX).collect::<Vec<_>>(), vec![(4, 78)]);
+
+        let mut iter = view.scan(Seq::MIN, Seq::MAX);
+        assert_eq!(iter.next(), Some((1, 12)));
+        assert_eq!(iter.next_back(), Some((2, 34)));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn prefetch() {
+        let mut view = TakeView::new(CountingView::<i32>::new(Default::default()), 2);
+        view.prefetch(Seq::MIN, Seq::MAX);
+        view.prefetch(10, 11);
+        view.prefetch(Seq::MAX - 1, Seq::MAX);
+        assert_eq!(
+            *view.inner_mut().counts.prefetches.borrow(),
+            vec![(Seq::MIN, 2), (10, 11), (Seq::MAX - 1, Seq::MAX)]
+        );
+
+        let mut view = TakeView::new(CountingView::<i32>::new(Default::default()), 0);
+        view.prefetch(Seq::MIN, Seq::MAX);
+        assert!(view.inner_mut().counts.prefetches.borrow().is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{Seq, View};
+
+/// Exposes only events within `window` sequence numbers of the inner view's current sequence number, e.g. to bound
+/// historical scans for rate limiting. The window slides forward as newer events arrive.
+pub struct WindowView<V: View> {
+    inner: V,
+    window: Seq,
+}
+
+impl<V: View> WindowView<V> {
+    pub fn new(inner: V, window: Seq) -> Self {
+        Self { inner, window }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.inner
+    }
+}
+
+impl<V: View> View for WindowView<V> {
+    type Event = V::Event;
+    type Iterator = V::Iterator;
+
+    fn scan(&mut self, start: Seq, end: Seq) -> Self::Iterator {
+        // events at or before the floor are hidden
+        let floor = self.get_current_seq().saturating_sub(self.window);
+        self.inner.scan(start.max(floor), end)
+    }
+
+    fn prefetch(&mut self, start: Seq, end: Seq) {
+        let floor = self.get_current_seq().saturating_sub(self.window);
+        self.inner.prefetch(start.max(floor), end)
+    }
+
+    fn get_current_seq(&mut self) -> Seq {
+        self.inner.get_current_seq()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowView;
+    use crate::table::vec::VecTable;
+    use crate::{Seq, Table, View};
+
+    #[test]
+    fn scan() {
+        let mut view = WindowView::new(VecTable::<i32>::new(), 3);
+        view.inner_mut().append(1..=10);
+
+        assert_eq!(view.get_current_seq(), 10);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(8, 8), (9, 9), (10, 10)]
+        );
+        assert_eq!(
+            view.scan_rev(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(10, 10), (9, 9), (8, 8)]
+        );
+        assert_eq!(view.scan(8, 9).collect::<Vec<_>>(), vec![(9, 9)]);
+        assert_eq!(view.scan(1, 5).count(), 0);
+        assert_eq!(view.scan(5, 1).count(), 0);
+
+        // the window slides with the current seq
+        view.inner_mut().append([11]);
+        assert_eq!(
+            view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(),
+            vec![(9, 9), (10, 10), (11, 11)]
+        );
+    }
+
+    #[test]
+    fn short_log() {
+        let mut view = WindowView::new(VecTable::<i32>::new(), 3);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).count(), 0);
+
+        view.inner_mut().append([1, 2]);
+        assert_eq!(view.scan(Seq::MIN, Seq::MAX).collect::<Vec<_>>(), vec![(1, 1), (2, 2)]);
+    }
+}
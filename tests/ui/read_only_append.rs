@@ -0,0 +1,8 @@
+use parasol_db::table::vec::VecTable;
+use parasol_db::view::read_only::ReadOnly;
+use parasol_db::Table;
+
+fn main() {
+    let mut view = ReadOnly::new(VecTable::<i32>::new());
+    view.append([1]);
+}
@@ -0,0 +1,64 @@
+//! Checks that `HashMapIndex::update` streams each event's updates without collecting them. This is its own test binary
+//! because it installs a counting global allocator, which would see allocations from every other test in the same binary.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use parasol_db::index::hash_map_index::{HashMapIndex, HashMapUpdate};
+use parasol_db::table::vec::VecTable;
+use parasol_db::{Index, Table};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn update_streams_updates() {
+    let mut table = VecTable::<u64>::new();
+    table.append(0..100);
+
+    let mut lazy = HashMapIndex::new(|event: u64| {
+        (0..1000).map(move |key| HashMapUpdate::Insert { key, value: event })
+    });
+    let mut collected = HashMapIndex::new(|event: u64| {
+        (0..1000)
+            .map(|key| HashMapUpdate::Insert { key, value: event })
+            .collect::<Vec<_>>()
+    });
+
+    // populate every key so later updates overwrite entries rather than grow the map
+    lazy.update(&mut table, 1);
+    collected.update(&mut table, 1);
+
+    // scanning copies the table, which is a fixed number of allocations, but nothing is allocated per event
+    let before = allocations();
+    lazy.update(&mut table, 100);
+    assert!(allocations() - before < 99);
+
+    let before = allocations();
+    collected.update(&mut table, 100);
+    assert!(allocations() - before >= 99);
+
+    assert_eq!(lazy.get_all(&mut table, 100), collected.get_all(&mut table, 100));
+    assert_eq!(lazy.get(&mut table, 50, &999), Some(49));
+}